@@ -25,7 +25,7 @@ fn tls_bench(c: &mut Criterion) {
 
     group.bench_function("create_delete_database", |b | {
 
-        b.to_async(Runtime::new().unwrap()).iter(|| client_setup());
+        b.to_async(Runtime::new().unwrap()).iter(client_setup);
     });
 }
 
@@ -0,0 +1,99 @@
+// Compares the line protocol encoder with the String based serializer it replaced
+
+use bytes::BytesMut;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use influxdb_rs::{line_protocol, Point, Value};
+
+fn sample_points(count: usize) -> Vec<Point<'static>> {
+    (0..count)
+        .map(|i| {
+            Point::new("cpu usage")
+                .add_tag("host", format!("server {}", i % 16))
+                .add_tag("region", "us-west,2")
+                .add_field("user", i as f64 * 0.25)
+                .add_field("count", i as i64)
+                .add_field("status", "ok \"green\"")
+                .add_field("active", i % 2 == 0)
+                .add_timestamp(1_700_000_000_000_000_000 + i as i64)
+        })
+        .collect()
+}
+
+// The serializer as it was before the encoder, kept here as the baseline
+fn string_serialization(points: &[Point]) -> String {
+    fn escape_keys_and_tags(value: &str) -> String {
+        value.replace(',', "\\,").replace('=', "\\=").replace(' ', "\\ ")
+    }
+
+    let mut line = String::new();
+
+    for point in points {
+        line.push_str(&point.measurement.replace(',', "\\,").replace(' ', "\\ "));
+
+        for (tag, value) in &point.tags {
+            line.push(',');
+            line.push_str(&escape_keys_and_tags(tag));
+            line.push('=');
+
+            match value {
+                Value::String(s) => line.push_str(&escape_keys_and_tags(s)),
+                Value::Float(f) => line.push_str(f.to_string().as_str()),
+                Value::Integer(i) => line.push_str(i.to_string().as_str()),
+                Value::UInteger(u) => line.push_str(u.to_string().as_str()),
+                Value::Boolean(b) => line.push_str(if *b { "true" } else { "false" }),
+            }
+        }
+
+        let mut was_first = true;
+
+        for (field, value) in &point.fields {
+            line.push(if was_first { ' ' } else { ',' });
+            was_first = false;
+            line.push_str(&escape_keys_and_tags(field));
+            line.push('=');
+
+            match value {
+                Value::String(s) => {
+                    line.push_str(&format!("\"{}\"", s.replace("\\\"", "\\\\\"").replace('\"', "\\\"")))
+                }
+                Value::Float(f) => line.push_str(&f.to_string()),
+                Value::Integer(i) => line.push_str(&format!("{i}i")),
+                Value::UInteger(u) => line.push_str(&format!("{u}u")),
+                Value::Boolean(b) => line.push_str(if *b { "true" } else { "false" }),
+            }
+        }
+
+        if let Some(t) = point.timestamp {
+            line.push(' ');
+            line.push_str(&t.to_string());
+        }
+
+        line.push('\n')
+    }
+
+    line
+}
+
+fn encode_bench(c: &mut Criterion) {
+    let points = sample_points(5_000);
+
+    let mut group = c.benchmark_group("line_protocol");
+    group.throughput(Throughput::Elements(points.len() as u64));
+
+    group.bench_function("string_serialization", |b| {
+        b.iter(|| string_serialization(black_box(&points)))
+    });
+
+    let mut buf = BytesMut::with_capacity(1 << 20);
+    group.bench_function("encode_points_reused_buffer", |b| {
+        b.iter(|| {
+            line_protocol::encode_points(black_box(&points), &mut buf);
+            buf.clear();
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, encode_bench);
+criterion_main!(benches);
@@ -40,7 +40,7 @@ async fn main() {
     let flux_query = format!("from(bucket: \"test_bucket\") 
     |> range(start: time(v: {:?}))
     |> filter(fn: (r) => r._measurement == \"test4\")
-    |> yield()", now.timestamp_nanos());
+    |> yield()", now.timestamp_nanos_opt().unwrap());
 
 
     let query = influxdb_rs::data_model::query::ReadQuery{
@@ -49,14 +49,15 @@ async fn main() {
         r#type: None,
         dialect: None,
         now: None,
+        params: None,
 
     };
 
     let result = client.query(Some(query)).await;
 
-    if result.is_ok(){
+    if let Ok(result) = result {
         // Prints Response Results in String
-        println!("{:?}", result.unwrap().text().await);
+        println!("{:?}", result.text().await);
 
     }
 
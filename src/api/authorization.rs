@@ -0,0 +1,63 @@
+use futures::prelude::*;
+
+use crate::client::Client;
+use crate::data_model::authorization::{AuthorizationFilter, AuthorizationResponse, UpdateAuthorizationRequest};
+use crate::data_model::links::PageOptions;
+use crate::error;
+
+
+impl Client {
+
+    /// List the authorizations matching the filter
+    pub async fn list_authorizations(&self, filter: &AuthorizationFilter) -> Result<Vec<AuthorizationResponse>, error::Error> {
+        self.authorizations_stream(filter, &PageOptions::default()).try_collect().await
+    }
+
+    /// Get an authorization by its ID
+    pub async fn get_authorization(&self, auth_id: &str) -> Result<AuthorizationResponse, error::Error> {
+        let url_format = format!("api/v2/authorizations/{}", auth_id);
+        let url = self.build_url(&url_format, None);
+
+        let fut = self.send(self.client.get(url.await));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            200 => {
+                let contents = res.json::<AuthorizationResponse>().await?;
+                Ok(contents)
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// Activate, deactivate or describe an authorization
+    pub async fn update_authorization(&self, auth_id: &str, request: UpdateAuthorizationRequest) -> Result<AuthorizationResponse, error::Error> {
+        let url_format = format!("api/v2/authorizations/{}", auth_id);
+        let url = self.build_url(&url_format, None);
+
+        let fut = self.send(self.client.patch(url.await).json(&request));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            200 => {
+                let contents = res.json::<AuthorizationResponse>().await?;
+                Ok(contents)
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// Delete an authorization, revoking its token
+    pub async fn delete_authorization(&self, auth_id: &str) -> Result<(), error::Error> {
+        let url_format = format!("api/v2/authorizations/{}", auth_id);
+        let url = self.build_url(&url_format, None);
+
+        let fut = self.send(self.client.delete(url.await));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            204 => Ok(()),
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+}
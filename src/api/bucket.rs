@@ -0,0 +1,89 @@
+use futures::prelude::*;
+
+use crate::client::Client;
+use crate::data_model::bucket::{AddLabel, BucketFilter, CreateBucketRequest, MultiBuckets, UpdateBucketRequest};
+use crate::data_model::links::PageOptions;
+use crate::error;
+
+
+impl Client {
+
+    /// Create a bucket, adding its labels once it exists
+    pub async fn create_bucket(&self, request: CreateBucketRequest) -> Result<MultiBuckets, error::Error> {
+        let url = self.build_url("api/v2/buckets", None);
+        let fut = self.send(self.client.post(url.await).json(&request));
+
+        let res = fut.await?;
+        let bucket = match res.status().as_u16() {
+            201 => res.json::<MultiBuckets>().await?,
+            _ => return Err(error::Error::from_response(res).await),
+        };
+
+        if request.labels.is_empty() {
+            return Ok(bucket);
+        }
+
+        for label_id in request.labels {
+            self.add_bucket_label(&bucket.id, &label_id).await?;
+        }
+
+        self.get_bucket(&bucket.id).await
+    }
+
+    /// Add a label to a bucket
+    pub async fn add_bucket_label(&self, bucket_id: &str, label_id: &str) -> Result<(), error::Error> {
+        let url_format = format!("api/v2/buckets/{}/labels", bucket_id);
+        let url = self.build_url(&url_format, None);
+
+        let body = AddLabel {
+            label_id: label_id.to_string(),
+        };
+
+        let fut = self.send(self.client.post(url.await).json(&body));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            201 => Ok(()),
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// Change the name, description or retention of a bucket
+    pub async fn update_bucket(&self, bucket_id: &str, request: UpdateBucketRequest) -> Result<MultiBuckets, error::Error> {
+        let url_format = format!("api/v2/buckets/{}", bucket_id);
+        let url = self.build_url(&url_format, None);
+
+        let fut = self.send(self.client.patch(url.await).json(&request));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            200 => {
+                let contents = res.json::<MultiBuckets>().await?;
+                Ok(contents)
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// Get a bucket by its ID
+    pub async fn get_bucket(&self, bucket_id: &str) -> Result<MultiBuckets, error::Error> {
+        let url_format = format!("api/v2/buckets/{}", bucket_id);
+        let url = self.build_url(&url_format, None);
+
+        let fut = self.send(self.client.get(url.await));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            200 => {
+                let contents = res.json::<MultiBuckets>().await?;
+                Ok(contents)
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// List the buckets matching the filter, across all pages
+    pub async fn find_buckets(&self, filter: &BucketFilter) -> Result<Vec<MultiBuckets>, error::Error> {
+        self.buckets_stream(filter, &PageOptions::default()).try_collect().await
+    }
+}
@@ -0,0 +1,465 @@
+use chrono::{DateTime, Duration};
+use reqwest::Response;
+use std::collections::HashMap;
+
+use crate::data_model::flux::{FluxColumn, FluxRecord, FluxTable, FluxValue};
+use crate::error;
+
+/// Parse a complete annotated CSV response into tables
+pub(crate) fn parse_tables(text: &str) -> Result<Vec<FluxTable>, error::Error> {
+    let mut parser = FluxCsvParser::default();
+    let mut tables: Vec<FluxTable> = Vec::new();
+    let mut rest = text;
+
+    while let Some((row, consumed)) = read_row(rest, true) {
+        rest = &rest[consumed..];
+
+        let record = parser.push_row(row)?;
+
+        while tables.len() < parser.tables {
+            tables.push(FluxTable {
+                columns: parser.columns.clone(),
+                records: Vec::new(),
+            });
+        }
+
+        if let Some(record) = record {
+            tables[record.table].records.push(record);
+        }
+    }
+
+    Ok(tables)
+}
+
+/// Reads records out of an annotated CSV response as its body arrives
+pub(crate) struct RecordReader {
+    res: Option<Response>,
+    buf: Vec<u8>,
+    pos: usize,
+    parser: FluxCsvParser,
+}
+
+impl RecordReader {
+    pub(crate) fn new(res: Response) -> Self {
+        RecordReader {
+            res: Some(res),
+            buf: Vec::new(),
+            pos: 0,
+            parser: FluxCsvParser::default(),
+        }
+    }
+
+    /// Next record of the response, `None` once the body is exhausted or after an error
+    pub(crate) async fn next_record(&mut self) -> Option<Result<FluxRecord, error::Error>> {
+        loop {
+            let eof = self.res.is_none();
+            let (text, invalid) = utf8_prefix(&self.buf[self.pos..], eof);
+
+            // Rows before invalid bytes are still handed out, a row running into them is not
+            if let Some((row, consumed)) = read_row(text, eof && !invalid) {
+                self.pos += consumed;
+                match self.parser.push_row(row) {
+                    Ok(Some(record)) => return Some(Ok(record)),
+                    Ok(None) => continue,
+                    Err(e) => return self.fail(e),
+                }
+            }
+
+            if invalid {
+                return self.fail(syntax_error("Response is not valid UTF-8".to_string()));
+            }
+
+            let res = self.res.as_mut()?;
+            match res.chunk().await {
+                Ok(Some(chunk)) => {
+                    self.buf.drain(..self.pos);
+                    self.pos = 0;
+                    self.buf.extend_from_slice(&chunk);
+                }
+                Ok(None) => self.res = None,
+                Err(e) => return self.fail(e.into()),
+            }
+        }
+    }
+
+    fn fail(&mut self, err: error::Error) -> Option<Result<FluxRecord, error::Error>> {
+        self.res = None;
+        self.buf.clear();
+        self.pos = 0;
+        Some(Err(err))
+    }
+}
+
+/// The valid UTF-8 at the front of `pending`, and whether invalid bytes follow it
+///
+/// A character cut off at the end of a chunk only counts as invalid once the body has ended.
+fn utf8_prefix(pending: &[u8], eof: bool) -> (&str, bool) {
+    match std::str::from_utf8(pending) {
+        Ok(text) => (text, false),
+        Err(e) => {
+            let text = std::str::from_utf8(&pending[..e.valid_up_to()]).unwrap_or_default();
+            (text, eof || e.error_len().is_some())
+        }
+    }
+}
+
+/// Split the first CSV row off the front of `buf`
+///
+/// Returns the cells and the number of bytes consumed, or `None` when `buf`
+/// does not hold a complete row yet. With `eof` set a trailing row without
+/// a line break is accepted.
+pub(crate) fn read_row(buf: &str, eof: bool) -> Option<(Vec<String>, usize)> {
+    if buf.is_empty() {
+        return None;
+    }
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = buf.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if let Some((_, '"')) = chars.peek() {
+                    chars.next();
+                    cell.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if cell.is_empty() => quoted = true,
+            ',' if !quoted => cells.push(std::mem::take(&mut cell)),
+            '\n' if !quoted => {
+                if cell.ends_with('\r') {
+                    cell.pop();
+                }
+                cells.push(cell);
+                return Some((cells, i + 1));
+            }
+            c => cell.push(c),
+        }
+    }
+
+    if eof {
+        if cell.ends_with('\r') {
+            cell.pop();
+        }
+        cells.push(cell);
+        Some((cells, buf.len()))
+    } else {
+        None
+    }
+}
+
+/// Incremental annotated CSV parser, fed one row at a time
+#[derive(Debug, Default)]
+pub(crate) struct FluxCsvParser {
+    datatypes: Vec<String>,
+    groups: Vec<String>,
+    defaults: Vec<String>,
+    /// Columns of the current table
+    pub(crate) columns: Vec<FluxColumn>,
+    /// Number of tables started so far
+    pub(crate) tables: usize,
+    has_header: bool,
+    in_annotations: bool,
+    last_table_id: Option<String>,
+}
+
+impl FluxCsvParser {
+    /// Feed a row, returning the record it holds if it is a data row
+    pub(crate) fn push_row(&mut self, row: Vec<String>) -> Result<Option<FluxRecord>, error::Error> {
+        // blank line separates tables with different schemas
+        if row.len() == 1 && row[0].is_empty() {
+            self.has_header = false;
+            return Ok(None);
+        }
+
+        if row[0].starts_with('#') {
+            if !self.in_annotations {
+                self.datatypes.clear();
+                self.groups.clear();
+                self.defaults.clear();
+                self.in_annotations = true;
+            }
+
+            let cells = row[1..].to_vec();
+            match row[0].as_str() {
+                "#datatype" => self.datatypes = cells,
+                "#group" => self.groups = cells,
+                "#default" => self.defaults = cells,
+                _ => {}
+            }
+
+            self.has_header = false;
+            return Ok(None);
+        }
+
+        if !self.has_header {
+            self.start_table(&row[1..]);
+            return Ok(None);
+        }
+
+        let cells = &row[1..];
+        if cells.len() != self.columns.len() {
+            return Err(syntax_error(format!(
+                "Expected {} columns but row has {}",
+                self.columns.len(),
+                cells.len()
+            )));
+        }
+
+        if self.is_error_table() {
+            let message = match cells[1].as_str() {
+                "" => cells[0].clone(),
+                reference => format!("{} (reference: {})", cells[0], reference),
+            };
+            return Err(syntax_error(message));
+        }
+
+        if let Some(table) = self.columns.iter().position(|c| c.name == "table") {
+            let id = &cells[table];
+            match self.last_table_id {
+                Some(ref last) if last != id => self.tables += 1,
+                _ => {}
+            }
+            self.last_table_id = Some(id.clone());
+        }
+
+        let mut values = HashMap::with_capacity(cells.len());
+        for (column, cell) in self.columns.iter().zip(cells) {
+            values.insert(column.name.clone(), parse_value(column, cell)?);
+        }
+
+        Ok(Some(FluxRecord {
+            table: self.tables - 1,
+            values,
+        }))
+    }
+
+    fn start_table(&mut self, names: &[String]) {
+        self.columns = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| FluxColumn {
+                index,
+                name: name.clone(),
+                data_type: self
+                    .datatypes
+                    .get(index)
+                    .cloned()
+                    .unwrap_or_else(|| "string".to_string()),
+                group: self.groups.get(index).map(|g| g == "true").unwrap_or(false),
+                default_value: self.defaults.get(index).cloned().unwrap_or_default(),
+            })
+            .collect();
+
+        self.tables += 1;
+        self.has_header = true;
+        self.in_annotations = false;
+        self.last_table_id = None;
+    }
+
+    fn is_error_table(&self) -> bool {
+        self.columns.len() == 2 && self.columns[0].name == "error" && self.columns[1].name == "reference"
+    }
+}
+
+fn syntax_error(message: String) -> error::Error {
+    error::Error {
+        inner: error::ErrorKind::SyntaxError(message),
+    }
+}
+
+/// Convert a cell to the datatype of its column
+pub(crate) fn parse_value(column: &FluxColumn, cell: &str) -> Result<FluxValue, error::Error> {
+    let cell = if cell.is_empty() {
+        column.default_value.as_str()
+    } else {
+        cell
+    };
+
+    if cell.is_empty() {
+        return Ok(FluxValue::Null);
+    }
+
+    let value = match column.data_type.as_str() {
+        "long" => cell.parse().ok().map(FluxValue::Long),
+        "unsignedLong" => cell.parse().ok().map(FluxValue::UnsignedLong),
+        "double" => match cell {
+            "+Inf" => Some(f64::INFINITY),
+            "-Inf" => Some(f64::NEG_INFINITY),
+            _ => cell.parse().ok(),
+        }
+        .map(FluxValue::Double),
+        "boolean" => cell.parse().ok().map(FluxValue::Boolean),
+        "dateTime:RFC3339" | "dateTime:RFC3339Nano" => {
+            DateTime::parse_from_rfc3339(cell).ok().map(FluxValue::DateTime)
+        }
+        "duration" => parse_duration(cell).map(FluxValue::Duration),
+        _ => Some(FluxValue::String(cell.to_string())),
+    };
+
+    value.ok_or_else(|| {
+        syntax_error(format!(
+            "Failed to parse column {} as {}: {:?}",
+            column.name, column.data_type, cell
+        ))
+    })
+}
+
+/// Parse a duration given either in nanoseconds or as e.g. `1h30m0.5s`
+pub(crate) fn parse_duration(value: &str) -> Option<Duration> {
+    if let Ok(nanos) = value.parse::<i64>() {
+        return Some(Duration::nanoseconds(nanos));
+    }
+
+    let (negative, mut rest) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut total = 0f64;
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_end].parse().ok()?;
+        rest = &rest[number_end..];
+
+        let unit_end = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let nanos_per_unit = match &rest[..unit_end] {
+            "ns" => 1f64,
+            "us" | "µs" | "μs" => 1e3,
+            "ms" => 1e6,
+            "s" => 1e9,
+            "m" => 60e9,
+            "h" => 3600e9,
+            "d" => 86400e9,
+            "w" => 604800e9,
+            _ => return None,
+        };
+        rest = &rest[unit_end..];
+
+        total += number * nanos_per_unit;
+    }
+
+    let nanos = if negative { -total } else { total };
+    Some(Duration::nanoseconds(nanos.round() as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = "#group,false,false,true,true,false,false,true,true,true\r
+#datatype,string,long,dateTime:RFC3339,dateTime:RFC3339,dateTime:RFC3339,double,string,string,string\r
+#default,_result,,,,,,,,\r
+,result,table,_start,_stop,_time,_value,_field,_measurement,host\r
+,,0,2022-01-01T00:00:00Z,2022-01-02T00:00:00Z,2022-01-01T10:00:00Z,1.5,usage,cpu,\"a,b\"\r
+,,0,2022-01-01T00:00:00Z,2022-01-02T00:00:00Z,2022-01-01T11:00:00Z,,usage,cpu,\"a,b\"\r
+,,1,2022-01-01T00:00:00Z,2022-01-02T00:00:00Z,2022-01-01T10:00:00Z,2,usage,cpu,\"say \"\"hi\"\"\"\r
+\r
+#group,false,false,true,false,false\r
+#datatype,string,long,string,boolean,unsignedLong\r
+#default,_result,,,,\r
+,result,table,_field,ok,count\r
+,,2,status,true,18446744073709551615\r
+\r
+";
+
+    #[test]
+    fn read_row_handles_quotes() {
+        let (row, consumed) = read_row("a,\"b,\"\"c\"\"\",d\nnext", false).unwrap();
+        assert_eq!(row, vec!["a", "b,\"c\"", "d"]);
+        assert_eq!(consumed, 14);
+
+        assert_eq!(read_row("a,\"b\nc", false), None);
+        assert_eq!(read_row("a,b", false), None);
+        assert_eq!(read_row("a,b", true), Some((vec!["a".to_string(), "b".to_string()], 3)));
+    }
+
+    #[test]
+    fn utf8_prefix_waits_only_for_cut_off_characters() {
+        // "é" cut off at the end of a chunk
+        assert_eq!(utf8_prefix(b"a,b\n\xc3", false), ("a,b\n", false));
+        assert_eq!(utf8_prefix(b"a,b\n\xc3", true), ("a,b\n", true));
+
+        // Invalid bytes in the middle of the body fail without waiting for more data
+        assert_eq!(utf8_prefix(b"a,b\n\xff,c\n", false), ("a,b\n", true));
+        assert_eq!(utf8_prefix(b"\xc3(", false), ("", true));
+    }
+
+    #[test]
+    fn parse_multiple_tables() {
+        let tables = parse_tables(RESPONSE).unwrap();
+
+        assert_eq!(tables.len(), 3);
+        assert_eq!(tables[0].records.len(), 2);
+        assert_eq!(tables[1].records.len(), 1);
+        assert_eq!(tables[2].records.len(), 1);
+
+        let first = &tables[0].records[0];
+        assert_eq!(first.value(), Some(&FluxValue::Double(1.5)));
+        assert_eq!(first.field(), Some("usage"));
+        assert_eq!(first.measurement(), Some("cpu"));
+        assert_eq!(first.get("host"), Some(&FluxValue::String("a,b".to_string())));
+        assert_eq!(first.get("result"), Some(&FluxValue::String("_result".to_string())));
+        assert_eq!(first.time().unwrap().to_rfc3339(), "2022-01-01T10:00:00+00:00");
+
+        assert_eq!(tables[0].records[1].value(), Some(&FluxValue::Null));
+        assert_eq!(tables[1].records[0].get("host"), Some(&FluxValue::String("say \"hi\"".to_string())));
+
+        assert_eq!(tables[2].columns.len(), 5);
+        assert_eq!(tables[2].records[0].get("ok"), Some(&FluxValue::Boolean(true)));
+        assert_eq!(tables[2].records[0].get("count"), Some(&FluxValue::UnsignedLong(u64::MAX)));
+
+        let group: Vec<&str> = tables[0].group_key().iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(group, vec!["_start", "_stop", "_field", "_measurement", "host"]);
+    }
+
+    #[test]
+    fn parse_without_annotations() {
+        let tables = parse_tables(",result,table,_value\n,_result,0,12\n").unwrap();
+
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].records[0].value(), Some(&FluxValue::String("12".to_string())));
+    }
+
+    #[test]
+    fn parse_error_table() {
+        let response = "#datatype,string,string\n#group,true,true\n#default,,\n,error,reference\n,failed to execute query,897\n";
+
+        match parse_tables(response) {
+            Err(e) => assert_eq!(e.to_string(), "failed to execute query (reference: 897)"),
+            Ok(_) => panic!("ERROR TABLE WAS NOT AN ERROR"),
+        }
+    }
+
+    #[test]
+    fn parse_bad_value() {
+        let response = "#datatype,string,long\n,result,_value\n,,abc\n";
+
+        match parse_tables(response) {
+            Err(e) => assert_eq!(e.to_string(), "Failed to parse column _value as long: \"abc\""),
+            Ok(_) => panic!("BAD VALUE WAS PARSED"),
+        }
+    }
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(parse_duration("1500"), Some(Duration::nanoseconds(1500)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("-1.5s"), Some(Duration::milliseconds(-1500)));
+        assert_eq!(parse_duration("2ms10us"), Some(Duration::microseconds(2010)));
+        assert_eq!(parse_duration("1mo"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+}
@@ -0,0 +1,312 @@
+use chrono::SecondsFormat;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use std::collections::hash_map;
+use std::fmt;
+
+use crate::data_model::flux::{FluxRecord, FluxValue};
+use crate::error;
+
+/// Deserialize a record into `T`, mapping columns to fields by name
+pub(crate) fn from_record<T: DeserializeOwned>(record: &FluxRecord) -> Result<T, error::Error> {
+    let access = RecordAccess {
+        iter: record.values.iter(),
+        column: None,
+    };
+
+    T::deserialize(RecordDeserializer(access)).map_err(|e| error::Error {
+        inner: error::ErrorKind::SyntaxError(e.0),
+    })
+}
+
+#[derive(Debug)]
+struct DeError(String);
+
+impl fmt::Display for DeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        DeError(msg.to_string())
+    }
+}
+
+struct RecordDeserializer<'a>(RecordAccess<'a>);
+
+impl<'de, 'a> de::Deserializer<'de> for RecordDeserializer<'a> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_map(self.0)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct RecordAccess<'a> {
+    iter: hash_map::Iter<'a, String, FluxValue>,
+    column: Option<(&'a String, &'a FluxValue)>,
+}
+
+impl<'de, 'a> MapAccess<'de> for RecordAccess<'a> {
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, DeError> {
+        match self.iter.next() {
+            Some((name, value)) => {
+                self.column = Some((name, value));
+                seed.deserialize(name.as_str().into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DeError> {
+        let (name, value) = self
+            .column
+            .take()
+            .ok_or_else(|| DeError("Value requested before column name".to_string()))?;
+
+        seed.deserialize(ValueDeserializer(value))
+            .map_err(|e| DeError(format!("Failed to deserialize column {}: {}", name, e)))
+    }
+}
+
+/// Deserializes a single cell, converting between compatible types
+struct ValueDeserializer<'a>(&'a FluxValue);
+
+impl<'a> ValueDeserializer<'a> {
+    fn invalid<E: de::Error>(&self, expected: &str) -> E {
+        E::custom(format!("expected {}, found {:?}", expected, self.0))
+    }
+
+    /// Integers, with times and durations given in nanoseconds
+    fn integer<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0 {
+            FluxValue::Long(i) => visitor.visit_i64(*i),
+            FluxValue::UnsignedLong(u) => visitor.visit_u64(*u),
+            FluxValue::DateTime(t) => match t.timestamp_nanos_opt() {
+                Some(nanos) => visitor.visit_i64(nanos),
+                None => Err(self.invalid("a time representable in nanoseconds")),
+            },
+            FluxValue::Duration(d) => match d.num_nanoseconds() {
+                Some(nanos) => visitor.visit_i64(nanos),
+                None => Err(self.invalid("a duration representable in nanoseconds")),
+            },
+            FluxValue::String(s) => match s.parse::<i64>() {
+                Ok(i) => visitor.visit_i64(i),
+                Err(_) => match s.parse::<u64>() {
+                    Ok(u) => visitor.visit_u64(u),
+                    Err(_) => Err(self.invalid("an integer")),
+                },
+            },
+            _ => Err(self.invalid("an integer")),
+        }
+    }
+
+    fn float<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0 {
+            FluxValue::Double(f) => visitor.visit_f64(*f),
+            FluxValue::Long(i) => visitor.visit_f64(*i as f64),
+            FluxValue::UnsignedLong(u) => visitor.visit_f64(*u as f64),
+            FluxValue::String(s) => match s.parse::<f64>() {
+                Ok(f) => visitor.visit_f64(f),
+                Err(_) => Err(self.invalid("a float")),
+            },
+            _ => Err(self.invalid("a float")),
+        }
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ValueDeserializer<'a> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0 {
+            FluxValue::Null => visitor.visit_none(),
+            FluxValue::Long(i) => visitor.visit_i64(*i),
+            FluxValue::UnsignedLong(u) => visitor.visit_u64(*u),
+            FluxValue::Double(f) => visitor.visit_f64(*f),
+            FluxValue::Boolean(b) => visitor.visit_bool(*b),
+            FluxValue::String(s) => visitor.visit_str(s),
+            FluxValue::DateTime(t) => {
+                visitor.visit_string(t.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
+            FluxValue::Duration(_) => self.integer(visitor),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0 {
+            FluxValue::Boolean(b) => visitor.visit_bool(*b),
+            FluxValue::String(s) => match s.parse::<bool>() {
+                Ok(b) => visitor.visit_bool(b),
+                Err(_) => Err(self.invalid("a boolean")),
+            },
+            _ => Err(self.invalid("a boolean")),
+        }
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.integer(visitor)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.integer(visitor)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.integer(visitor)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.integer(visitor)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.integer(visitor)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.integer(visitor)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.integer(visitor)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.integer(visitor)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.float(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.float(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0 {
+            FluxValue::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        match self.0 {
+            FluxValue::String(s) => visitor.visit_enum(s.as_str().into_deserializer()),
+            _ => Err(self.invalid("a string")),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::flux_csv::parse_tables;
+    use chrono::{DateTime, Utc};
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Cpu {
+        #[serde(rename = "_time")]
+        time: DateTime<Utc>,
+        #[serde(rename = "_value")]
+        value: f64,
+        #[serde(rename = "_field")]
+        field: String,
+        #[serde(rename = "_measurement")]
+        measurement: String,
+        host: String,
+        cores: u32,
+        active: bool,
+        note: Option<String>,
+    }
+
+    const RESPONSE: &str = "#group,false,false,false,false,true,true,true,false,false,false
+#datatype,string,long,dateTime:RFC3339Nano,long,string,string,string,long,boolean,string
+#default,_result,,,,,,,,,
+,result,table,_time,_value,_field,_measurement,host,cores,active,note
+,,0,2022-01-01T10:00:00.000000001Z,3,usage,cpu,server01,8,true,
+";
+
+    #[test]
+    fn deserialize_record() {
+        let tables = parse_tables(RESPONSE).unwrap();
+        let cpu: Cpu = from_record(&tables[0].records[0]).unwrap();
+
+        assert_eq!(
+            cpu,
+            Cpu {
+                time: "2022-01-01T10:00:00.000000001Z".parse().unwrap(),
+                value: 3.0,
+                field: "usage".to_string(),
+                measurement: "cpu".to_string(),
+                host: "server01".to_string(),
+                cores: 8,
+                active: true,
+                note: None,
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_time_as_nanos() {
+        #[derive(Deserialize)]
+        struct Row {
+            #[serde(rename = "_time")]
+            time: i64,
+        }
+
+        let tables = parse_tables(RESPONSE).unwrap();
+        let row: Row = from_record(&tables[0].records[0]).unwrap();
+
+        assert_eq!(row.time, 1_641_031_200_000_000_001);
+    }
+
+    #[test]
+    fn error_names_column() {
+        #[derive(Debug, Deserialize)]
+        struct Row {
+            #[allow(dead_code)]
+            host: i64,
+        }
+
+        let tables = parse_tables(RESPONSE).unwrap();
+        let err = from_record::<Row>(&tables[0].records[0]).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Failed to deserialize column host: expected an integer, found String(\"server01\")"
+        );
+    }
+}
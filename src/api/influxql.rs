@@ -0,0 +1,85 @@
+use crate::client::Client;
+use crate::data_model::influxql::InfluxQlResponse;
+use crate::{error, Precision};
+
+
+impl Client {
+
+    /// InfluxQL query through the v1 compatible /query endpoint
+    ///
+    /// Uses basic authentication when set with `set_authentication`, the token otherwise.
+    /// Errors of individual statements are kept in their `StatementResult`.
+    pub async fn query_influxql(
+        &self,
+        db: &str,
+        rp: Option<&str>,
+        query: &str,
+        epoch: Option<Precision>,
+    ) -> Result<InfluxQlResponse, error::Error> {
+        self.send_influxql(db, rp, query, epoch, None).await
+    }
+
+    /// InfluxQL query with the results streamed back in chunks of `chunk_size` rows
+    ///
+    /// The chunks are merged into a single response.
+    pub async fn query_influxql_chunked(
+        &self,
+        db: &str,
+        rp: Option<&str>,
+        query: &str,
+        epoch: Option<Precision>,
+        chunk_size: usize,
+    ) -> Result<InfluxQlResponse, error::Error> {
+        self.send_influxql(db, rp, query, epoch, Some(chunk_size)).await
+    }
+
+    async fn send_influxql(
+        &self,
+        db: &str,
+        rp: Option<&str>,
+        query: &str,
+        epoch: Option<Precision>,
+        chunk_size: Option<usize>,
+    ) -> Result<InfluxQlResponse, error::Error> {
+        let chunk_size = chunk_size.map(|size| size.to_string());
+
+        let mut param = vec![("db", db)];
+
+        if let Some(rp) = rp {
+            param.push(("rp", rp));
+        }
+
+        if let Some(ref epoch) = epoch {
+            param.push(("epoch", epoch.to_v1_str()));
+        }
+
+        if let Some(ref size) = chunk_size {
+            param.push(("chunked", "true"));
+            param.push(("chunk_size", size.as_str()));
+        }
+
+        let url = self.build_url("query", Some(param));
+        let builder = self.client.post(url.await).form(&[("q", query)]);
+
+        let res = self.send(self.legacy_auth(builder)).await?;
+        if res.status().as_u16() != 200 {
+            return Err(error::Error::from_response(res).await);
+        }
+
+        let text = res.text().await?;
+        let mut response = InfluxQlResponse::default();
+
+        for chunk in serde_json::Deserializer::from_str(&text).into_iter::<InfluxQlResponse>() {
+            let chunk = chunk.map_err(|e| error::Error{
+                inner: error::ErrorKind::SyntaxError(e.to_string())})?;
+            response.merge(chunk);
+        }
+
+        match response.error {
+            Some(err) => Err(error::Error{
+                inner: error::ErrorKind::SyntaxError(err)}),
+            None => Ok(response),
+        }
+    }
+}
+
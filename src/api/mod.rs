@@ -1,2 +1,20 @@
 /// InfluxDB Query Module
-pub mod query;
\ No newline at end of file
+pub mod query;
+/// InfluxQL through the v1 compatible /query endpoint
+pub mod influxql;
+/// Bucket Management
+pub mod bucket;
+/// Organization Management
+pub mod org;
+/// Authorization (API token) Management
+pub mod authorization;
+/// User Management
+pub mod user;
+/// Username/password sessions through signin and signout
+pub mod session;
+/// Listings that follow `links.next` across pages
+pub mod paginate;
+/// Annotated CSV parser for Flux query results
+pub(crate) mod flux_csv;
+/// Deserialize Flux records into user structs
+pub(crate) mod flux_de;
\ No newline at end of file
@@ -0,0 +1,148 @@
+use futures::prelude::*;
+
+use crate::client::Client;
+use crate::data_model::links::PageOptions;
+use crate::data_model::org::{AddResourceMember, CreateOrgRequest, OrgStruct, ResourceMember, ResourceMembers, UpdateOrgRequest};
+use crate::error;
+
+
+impl Client {
+
+    /// List all organizations, across all pages
+    pub async fn list_orgs(&self) -> Result<Vec<OrgStruct>, error::Error> {
+        self.orgs_stream(&PageOptions::default()).try_collect().await
+    }
+
+    /// Get an organization by its ID
+    pub async fn get_org(&self, org_id: &str) -> Result<OrgStruct, error::Error> {
+        let url_format = format!("api/v2/orgs/{}", org_id);
+        let url = self.build_url(&url_format, None);
+
+        let fut = self.send(self.client.get(url.await));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            200 => {
+                let contents = res.json::<OrgStruct>().await?;
+                Ok(contents)
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// Create an organization
+    pub async fn create_org(&self, request: CreateOrgRequest) -> Result<OrgStruct, error::Error> {
+        let url = self.build_url("api/v2/orgs", None);
+        let fut = self.send(self.client.post(url.await).json(&request));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            201 => {
+                let contents = res.json::<OrgStruct>().await?;
+                Ok(contents)
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// Change the name or description of an organization
+    pub async fn update_org(&self, org_id: &str, request: UpdateOrgRequest) -> Result<OrgStruct, error::Error> {
+        let url_format = format!("api/v2/orgs/{}", org_id);
+        let url = self.build_url(&url_format, None);
+
+        let fut = self.send(self.client.patch(url.await).json(&request));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            200 => {
+                let contents = res.json::<OrgStruct>().await?;
+                Ok(contents)
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// Delete an organization together with its buckets, tasks and dashboards
+    pub async fn delete_org(&self, org_id: &str) -> Result<(), error::Error> {
+        let url_format = format!("api/v2/orgs/{}", org_id);
+        let url = self.build_url(&url_format, None);
+
+        let fut = self.send(self.client.delete(url.await));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            204 => Ok(()),
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// Members of an organization
+    pub async fn list_org_members(&self, org_id: &str) -> Result<Vec<ResourceMember>, error::Error> {
+        self.list_org_users(org_id, "members").await
+    }
+
+    /// Add a user as member of an organization
+    pub async fn add_org_member(&self, org_id: &str, user_id: &str) -> Result<ResourceMember, error::Error> {
+        self.add_org_user(org_id, "members", user_id).await
+    }
+
+    /// Remove a member from an organization
+    pub async fn remove_org_member(&self, org_id: &str, user_id: &str) -> Result<(), error::Error> {
+        self.remove_org_user(org_id, "members", user_id).await
+    }
+
+    /// Owners of an organization
+    pub async fn list_org_owners(&self, org_id: &str) -> Result<Vec<ResourceMember>, error::Error> {
+        self.list_org_users(org_id, "owners").await
+    }
+
+    /// Add a user as owner of an organization
+    pub async fn add_org_owner(&self, org_id: &str, user_id: &str) -> Result<ResourceMember, error::Error> {
+        self.add_org_user(org_id, "owners", user_id).await
+    }
+
+    /// Remove an owner from an organization
+    pub async fn remove_org_owner(&self, org_id: &str, user_id: &str) -> Result<(), error::Error> {
+        self.remove_org_user(org_id, "owners", user_id).await
+    }
+
+    async fn list_org_users(&self, org_id: &str, role: &str) -> Result<Vec<ResourceMember>, error::Error> {
+        let url_format = format!("api/v2/orgs/{}/{}", org_id, role);
+        let url = self.endpoint_url(&url_format, None);
+
+        self.paginate::<ResourceMembers>(url).try_collect().await
+    }
+
+    async fn add_org_user(&self, org_id: &str, role: &str, user_id: &str) -> Result<ResourceMember, error::Error> {
+        let url_format = format!("api/v2/orgs/{}/{}", org_id, role);
+        let url = self.build_url(&url_format, None);
+
+        let body = AddResourceMember {
+            id: user_id.to_string(),
+        };
+
+        let fut = self.send(self.client.post(url.await).json(&body));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            201 => {
+                let contents = res.json::<ResourceMember>().await?;
+                Ok(contents)
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    async fn remove_org_user(&self, org_id: &str, role: &str, user_id: &str) -> Result<(), error::Error> {
+        let url_format = format!("api/v2/orgs/{}/{}/{}", org_id, role, user_id);
+        let url = self.build_url(&url_format, None);
+
+        let fut = self.send(self.client.delete(url.await));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            204 => Ok(()),
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+}
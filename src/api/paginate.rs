@@ -0,0 +1,197 @@
+use futures::prelude::*;
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+
+use crate::client::Client;
+use crate::data_model::authorization::{AuthorizationFilter, AuthorizationResponse, Authorizations};
+use crate::data_model::bucket::{BucketFilter, Buckets, MultiBuckets};
+use crate::data_model::labels::{Labels, LabelsResponse};
+use crate::data_model::links::{Links, PageOptions};
+use crate::data_model::org::{OrgStruct, Orgs, ResourceMember, ResourceMembers};
+use crate::data_model::task::{Task, Tasks};
+use crate::data_model::user::{ListUserResponse, UserFilter, UserResponse};
+use crate::error;
+
+/// Single page of a listing
+pub(crate) trait Page: DeserializeOwned {
+    type Item;
+
+    fn links(&self) -> &Links;
+
+    fn into_items(self) -> Vec<Self::Item>;
+}
+
+macro_rules! impl_page {
+    ($page:ty, $field:ident, $item:ty) => {
+        impl Page for $page {
+            type Item = $item;
+
+            fn links(&self) -> &Links {
+                &self.links
+            }
+
+            fn into_items(self) -> Vec<$item> {
+                self.$field
+            }
+        }
+    };
+}
+
+impl_page!(Buckets, buckets, MultiBuckets);
+impl_page!(Orgs, orgs, OrgStruct);
+impl_page!(ListUserResponse, users, UserResponse);
+impl_page!(Authorizations, authorizations, AuthorizationResponse);
+impl_page!(LabelsResponse, labels, Labels);
+impl_page!(Tasks, tasks, Task);
+impl_page!(ResourceMembers, users, ResourceMember);
+
+struct PageState<T> {
+    client: Client,
+    next: Option<Url>,
+    items: VecDeque<T>,
+}
+
+/// Resolve `links.next` against the host, keeping any path prefix of the host
+fn next_url(host: &Url, current: &Url, next: Option<&str>) -> Option<Url> {
+    let next = host.join(next?.trim_start_matches('/')).ok()?;
+
+    // Guard against servers handing back the page that was just fetched
+    if &next == current {
+        None
+    } else {
+        Some(next)
+    }
+}
+
+fn with_page<'a>(mut param: Vec<(&'a str, &'a str)>, page: &'a [(&'static str, String)]) -> Vec<(&'a str, &'a str)> {
+    param.extend(page.iter().map(|(k, v)| (*k, v.as_str())));
+    param
+}
+
+impl Client {
+
+    /// Items of all pages of a listing, starting at `url`
+    pub(crate) fn paginate<P>(&self, url: Url) -> impl Stream<Item = Result<P::Item, error::Error>> + Send + 'static
+    where
+        P: Page + Send + 'static,
+        P::Item: Send + 'static,
+    {
+        let state = PageState {
+            client: self.clone(),
+            next: Some(url),
+            items: VecDeque::new(),
+        };
+
+        stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(item) = state.items.pop_front() {
+                    return Some((Ok(item), state));
+                }
+
+                let url = state.next.take()?;
+                match state.client.get_page::<P>(url.clone()).await {
+                    Ok(page) => {
+                        state.next = next_url(&state.client.host, &url, page.links().next.as_deref());
+                        state.items.extend(page.into_items());
+                    }
+                    Err(err) => return Some((Err(err), state)),
+                }
+            }
+        })
+    }
+
+    async fn get_page<P: Page>(&self, url: Url) -> Result<P, error::Error> {
+        let res = self.send(self.client.get(url)).await?;
+
+        match res.status().as_u16() {
+            200 => Ok(res.json::<P>().await?),
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// Buckets matching the filter, across all pages
+    pub fn buckets_stream(&self, filter: &BucketFilter, page: &PageOptions) -> impl Stream<Item = Result<MultiBuckets, error::Error>> + Send + 'static {
+        let page = page.params();
+        let url = self.endpoint_url("api/v2/buckets", Some(with_page(filter.params(), &page)));
+        self.paginate::<Buckets>(url)
+    }
+
+    /// Organizations, across all pages
+    pub fn orgs_stream(&self, page: &PageOptions) -> impl Stream<Item = Result<OrgStruct, error::Error>> + Send + 'static {
+        let page = page.params();
+        let url = self.endpoint_url("api/v2/orgs", Some(with_page(Vec::new(), &page)));
+        self.paginate::<Orgs>(url)
+    }
+
+    /// Users matching the filter, across all pages
+    pub fn users_stream(&self, filter: &UserFilter, page: &PageOptions) -> impl Stream<Item = Result<UserResponse, error::Error>> + Send + 'static {
+        let page = page.params();
+        let url = self.endpoint_url("api/v2/users", Some(with_page(filter.params(), &page)));
+        self.paginate::<ListUserResponse>(url)
+    }
+
+    /// Authorizations matching the filter, across all pages
+    pub fn authorizations_stream(&self, filter: &AuthorizationFilter, page: &PageOptions) -> impl Stream<Item = Result<AuthorizationResponse, error::Error>> + Send + 'static {
+        let page = page.params();
+        let url = self.endpoint_url("api/v2/authorizations", Some(with_page(filter.params(), &page)));
+        self.paginate::<Authorizations>(url)
+    }
+
+    /// Labels, across all pages
+    pub fn labels_stream(&self, page: &PageOptions) -> impl Stream<Item = Result<Labels, error::Error>> + Send + 'static {
+        let page = page.params();
+        let url = self.endpoint_url("api/v2/labels", Some(with_page(Vec::new(), &page)));
+        self.paginate::<LabelsResponse>(url)
+    }
+
+    /// Tasks, across all pages. Tasks are paged with `after` rather than `offset`
+    pub fn tasks_stream(&self, page: &PageOptions) -> impl Stream<Item = Result<Task, error::Error>> + Send + 'static {
+        let page = page.params();
+        let url = self.endpoint_url("api/v2/tasks", Some(with_page(Vec::new(), &page)));
+        self.paginate::<Tasks>(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_next_link() {
+        let host = Url::parse("http://localhost:8086/influx/").unwrap();
+        let current = host.join("api/v2/buckets?limit=2").unwrap();
+
+        let next = next_url(&host, &current, Some("/api/v2/buckets?limit=2&offset=2"));
+        assert_eq!(next.unwrap().as_str(), "http://localhost:8086/influx/api/v2/buckets?limit=2&offset=2");
+
+        assert_eq!(next_url(&host, &current, None), None);
+        assert_eq!(next_url(&host, &current, Some("/api/v2/buckets?limit=2")), None);
+    }
+
+    #[test]
+    fn deserialize_page() {
+        let body = r#"{"links":{"self":"/api/v2/tasks?limit=1","next":"/api/v2/tasks?after=0a&limit=1"},
+            "tasks":[{"id":"0a","orgID":"01","name":"downsample","status":"active","flux":"option task = {name: \"downsample\", every: 1h}"}]}"#;
+
+        let page: Tasks = serde_json::from_str(body).unwrap();
+        assert_eq!(page.links().next.as_deref(), Some("/api/v2/tasks?after=0a&limit=1"));
+
+        let tasks = page.into_items();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].name, "downsample");
+
+        // Authorizations are not paged and only carry a self link
+        let page: Authorizations = serde_json::from_str(r#"{"authorizations":[]}"#).unwrap();
+        assert!(page.links().next.is_none());
+    }
+
+    #[test]
+    fn page_params() {
+        let page = PageOptions::default().limit(10).offset(20).after("0a");
+        let params = page.params();
+        let params = with_page(vec![("name", "metrics")], &params);
+
+        assert_eq!(params, vec![("name", "metrics"), ("limit", "10"), ("offset", "20"), ("after", "0a")]);
+    }
+}
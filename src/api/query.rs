@@ -1,10 +1,13 @@
-use crate::data_model::query::ReadQuery;
+use crate::data_model::query::{AnalyzeQueryResponse, AstResponse, Dialect, FluxSuggestion, FluxSuggestions, LanguageRequest, ReadQuery};
+use crate::data_model::flux::{FluxRecord, FluxTable};
+use crate::api::flux_csv;
 use crate::error;
 use crate::client::Client;
 use serde_json::json;
-use futures::prelude::*;
+use serde::de::DeserializeOwned;
+use futures::stream::{self, Stream};
 use reqwest::Response;
-use crate::serialization;
+use crate::RetryPolicy;
 
 
 impl Client {
@@ -14,47 +17,217 @@ impl Client {
     pub async fn query(
         &self,
         query: Option<ReadQuery>,
+    ) -> Result<Response, error::Error> {
+        self.query_with_retry(query, &self.retry_policy).await
+    }
+
+    /// Query api/v2/query, overriding the client's retry policy
+    pub async fn query_with_retry(
+        &self,
+        query: Option<ReadQuery>,
+        retry: &RetryPolicy,
+    ) -> Result<Response, error::Error> {
+        if let Some(query) = query {
+            self.send_query(json!(query), retry).await
+        } else {
+            Err(error::Error{
+                inner: error::ErrorKind::Unknown("No flux query to serialize".to_string())})
+        }
+    }
+
+    /// Query api/v2/query and parse the annotated CSV response into tables
+    ///
+    /// Requests the header, delimiter and annotations the parser needs, overriding
+    /// those of a dialect set on the query.
+    pub async fn query_tables(
+        &self,
+        query: ReadQuery,
+    ) -> Result<Vec<FluxTable>, error::Error> {
+        let body = json!(with_dialect(query));
+
+        let res = self.send_query(body, &self.retry_policy).await?;
+        let text = res.text().await?;
+
+        flux_csv::parse_tables(&text)
+    }
+
+    /// Query api/v2/query and deserialize every record into `T`
+    ///
+    /// Fields are matched to columns by name, e.g. `#[serde(rename = "_time")]`.
+    pub async fn query_as<T: DeserializeOwned>(
+        &self,
+        query: ReadQuery,
+    ) -> Result<Vec<T>, error::Error> {
+        let tables = self.query_tables(query).await?;
+
+        tables
+            .iter()
+            .flat_map(|table| table.records.iter())
+            .map(|record| record.deserialize())
+            .collect()
+    }
+
+    /// Query api/v2/query and stream the records as the response arrives
+    ///
+    /// Requests the header, delimiter and annotations the parser needs, overriding
+    /// those of a dialect set on the query.
+    ///
+    /// The response body is parsed incrementally, so memory stays bounded
+    /// no matter how many rows the query returns. Dropping the stream
+    /// cancels the request.
+    pub async fn query_stream(
+        &self,
+        query: ReadQuery,
+    ) -> Result<impl Stream<Item = Result<FluxRecord, error::Error>>, error::Error> {
+        let body = json!(with_dialect(query));
+
+        let res = self.send_query(body, &self.retry_policy).await?;
+        let reader = flux_csv::RecordReader::new(res);
+
+        Ok(stream::unfold(reader, |mut reader| async move {
+            reader.next_record().await.map(|record| (record, reader))
+        }))
+    }
+
+    /// Analyze a Flux query for syntax errors, api/v2/query/analyze
+    ///
+    /// An empty `errors` list means the query is valid.
+    pub async fn analyze_query(
+        &self,
+        query: &ReadQuery,
+    ) -> Result<AnalyzeQueryResponse, error::Error> {
+        let url = self.build_url("api/v2/query/analyze", None);
+        let fut = self.send(self.client.post(url.await).json(query));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            200 => {
+                let contents = res.json::<AnalyzeQueryResponse>().await?;
+                Ok(contents)
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// Parse a Flux query into its abstract syntax tree, api/v2/query/ast
+    pub async fn query_ast(
+        &self,
+        query: &str,
+    ) -> Result<AstResponse, error::Error> {
+        let url = self.build_url("api/v2/query/ast", None);
+        let body = LanguageRequest {
+            query: query.to_string(),
+        };
+        let fut = self.send(self.client.post(url.await).json(&body));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            200 => {
+                let contents = res.json::<AstResponse>().await?;
+                Ok(contents)
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// List the Flux functions available for suggestions, api/v2/query/suggestions
+    pub async fn query_suggestions(&self) -> Result<Vec<FluxSuggestion>, error::Error> {
+        let url = self.build_url("api/v2/query/suggestions", None);
+        let fut = self.send(self.client.get(url.await));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            200 => {
+                let contents = res.json::<FluxSuggestions>().await?;
+                Ok(contents.funcs)
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// Get the parameters of a single Flux function, api/v2/query/suggestions/{name}
+    pub async fn query_suggestion(&self, name: &str) -> Result<FluxSuggestion, error::Error> {
+        let mut url = self.build_url("api/v2/query/suggestions", None).await;
+        url.path_segments_mut()
+            .map_err(|_| error::Error{
+                inner: error::ErrorKind::Communication("Host cannot be a base URL".to_string())})?
+            .push(name);
+
+        let fut = self.send(self.client.get(url));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            200 => {
+                let contents = res.json::<FluxSuggestion>().await?;
+                Ok(contents)
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// Post a JSON query body to api/v2/query
+    pub(crate) async fn send_query(
+        &self,
+        body: serde_json::Value,
+        retry: &RetryPolicy,
     ) -> Result<Response, error::Error> {
         let param = vec![("org", self.org.as_str())];
 
 
-        let url = self.build_url("api/v2/query", Some(param));
-
-        if query.is_some() {
-            let builder = self.client.post(url.await).body(json!(query.unwrap()).to_string());
-            let resp_future = builder.bearer_auth(self.jwt_token.clone().unwrap()).send().boxed();
-
-            let res = resp_future.await?;
-            match res.status().as_u16() {
-                200 => {
-                    return Ok(res);
-                }
-                400 => {
-                    let json_data = res.text().await?;
-    
-                    return Err(error::Error{
-                        inner: error::ErrorKind::SyntaxError(serialization::conversion(
-                        &json_data,
-                    ))});
-                }
-                401 | 403 => {
-                        return Err(error::Error{
-                            inner: error::ErrorKind::InvalidCredentials(
-                            "Invalid authentication credentials.".to_string()
-                        )});
-                }
-                _ => {
-                    let err = res.text().await?;
-                    return Err(error::Error{
-                        inner: error::ErrorKind::Unknown(err)});
-                }
+        let url = self.build_url("api/v2/query", Some(param)).await;
+
+        let body = body.to_string();
+        let res = retry.send(self, || {
+            self.client.post(url.clone()).body(body.clone())
+        }).await?;
+        match res.status().as_u16() {
+            200 => {
+                Ok(res)
             }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+}
 
-        } else {
-            return Err(error::Error{
-                inner: error::ErrorKind::Unknown("No flux query to serialize".to_string())});
+/// Request the annotated CSV the parser expects
+///
+/// Header, delimiter, annotations and comment prefix of a dialect set on the
+/// query are overridden, only its date time format is kept.
+fn with_dialect(mut query: ReadQuery) -> ReadQuery {
+    let required = Dialect::default();
+
+    query.dialect = Some(match query.dialect.take() {
+        Some(dialect) => Dialect {
+            date_time_format: dialect.date_time_format.or(required.date_time_format),
+            ..required
+        },
+        None => required,
+    });
+    query
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_model::query::{Annotation, DateTimeFormat};
+
+    #[test]
+    fn dialect_is_enforced() {
+        let query = ReadQuery {
+            dialect: Some(Dialect::default()
+                .header(false)
+                .annotations(vec![Annotation::Group])
+                .date_time_format(DateTimeFormat::RFC3339Nano)),
+            ..Default::default()
         };
 
-        
+        let dialect = with_dialect(query).dialect.unwrap();
+        assert_eq!(dialect.header, Some(true));
+        assert_eq!(dialect.delimiter.as_deref(), Some(","));
+        assert_eq!(dialect.annotations, Dialect::default().annotations);
+        assert_eq!(dialect.date_time_format, Some(DateTimeFormat::RFC3339Nano));
+
+        let dialect = with_dialect(ReadQuery::default()).dialect.unwrap();
+        assert_eq!(dialect, Dialect::default());
     }
-}
\ No newline at end of file
+}
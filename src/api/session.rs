@@ -0,0 +1,150 @@
+use reqwest::{header, Request, Url};
+use std::fmt;
+use tokio::sync::RwLock;
+
+use crate::client::Client;
+use crate::ClientBuilder;
+use crate::error;
+
+/// Username and password a client signs in with, and the session cookie it got back
+pub(crate) struct Session {
+    username: String,
+    password: String,
+    cookie: RwLock<Option<header::HeaderValue>>,
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Session {
+    pub(crate) fn new(username: String, password: String) -> Self {
+        Session {
+            username,
+            password,
+            cookie: RwLock::new(None),
+        }
+    }
+
+    /// Add the session cookie, returns whether there was one
+    pub(crate) async fn attach(&self, request: &mut Request) -> bool {
+        match *self.cookie.read().await {
+            Some(ref cookie) => {
+                request.headers_mut().insert(header::COOKIE, cookie.clone());
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// `name=value` pairs of the Set-Cookie headers, joined for a Cookie header
+fn session_cookie(headers: &header::HeaderMap) -> Option<header::HeaderValue> {
+    let cookies: Vec<&str> = headers
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| value.split(';').next())
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .collect();
+
+    if cookies.is_empty() {
+        return None;
+    }
+
+    header::HeaderValue::from_str(&cookies.join("; ")).ok()
+}
+
+impl Client {
+
+    /// Create a client that signs in with a username and password instead of a token
+    ///
+    /// The session cookie is shared by clones of the client and renewed when it expires.
+    /// Call `signout` when done to end the session on the server.
+    pub async fn new_with_session<T>(host: Url, bucket: T, org: T, username: T, password: T) -> Result<Self, error::Error>
+    where
+        T: Into<String>,
+    {
+        ClientBuilder::new(host, bucket, org)
+            .session(username, password)
+            .build()
+            .await
+    }
+
+    /// Sign in through api/v2/signin, replacing the session cookie
+    pub async fn signin(&self) -> Result<(), error::Error> {
+        let session = self.session.as_ref().ok_or_else(|| error::Error{
+            inner: error::ErrorKind::InvalidCredentials(
+            "Client was not created with a username and password.".to_string(),
+        )})?;
+
+        let url = self.endpoint_url("api/v2/signin", None);
+        let request = self.client.post(url)
+            .basic_auth(&session.username, Some(&session.password))
+            .build()?;
+        let fut = self.execute(request);
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            204 => {
+                let cookie = session_cookie(res.headers()).ok_or_else(|| error::Error{
+                    inner: error::ErrorKind::InvalidCredentials(
+                    "Sign in did not return a session cookie.".to_string(),
+                )})?;
+
+                *session.cookie.write().await = Some(cookie);
+                Ok(())
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// End the session through api/v2/signout
+    ///
+    /// Requests sent afterwards are not signed in again. Does nothing for token clients.
+    pub async fn signout(&self) -> Result<(), error::Error> {
+        let session = match self.session {
+            Some(ref session) => session,
+            None => return Ok(()),
+        };
+
+        let cookie = match session.cookie.write().await.take() {
+            Some(cookie) => cookie,
+            None => return Ok(()),
+        };
+
+        let url = self.endpoint_url("api/v2/signout", None);
+        let request = self.client.post(url).header(header::COOKIE, cookie).build()?;
+        let fut = self.execute(request);
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            // The session had already expired
+            204 | 401 => Ok(()),
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cookie_from_set_cookie() {
+        let mut headers = header::HeaderMap::new();
+        headers.append(header::SET_COOKIE, header::HeaderValue::from_static(
+            "influxdb-oss-session=abc123; Path=/api/; Expires=Sun, 18 Oct 2026 09:00:00 GMT; HttpOnly"));
+        headers.append(header::SET_COOKIE, header::HeaderValue::from_static("other=1"));
+
+        let cookie = session_cookie(&headers).unwrap();
+        assert_eq!(cookie, "influxdb-oss-session=abc123; other=1");
+
+        assert_eq!(session_cookie(&header::HeaderMap::new()), None);
+    }
+}
@@ -0,0 +1,96 @@
+use crate::client::Client;
+use crate::data_model::user::{PasswordRequest, UpdateUserRequest, UserResponse};
+use crate::error;
+
+
+impl Client {
+
+    /// Get a user by its ID
+    pub async fn get_user(&self, user_id: &str) -> Result<UserResponse, error::Error> {
+        let url_format = format!("api/v2/users/{}", user_id);
+        self.get_user_at(&url_format).await
+    }
+
+    /// User the client is authenticated as
+    pub async fn get_me(&self) -> Result<UserResponse, error::Error> {
+        self.get_user_at("api/v2/me").await
+    }
+
+    /// Change the name or status of a user
+    pub async fn update_user(&self, user_id: &str, request: UpdateUserRequest) -> Result<UserResponse, error::Error> {
+        let url_format = format!("api/v2/users/{}", user_id);
+        let url = self.build_url(&url_format, None);
+
+        let fut = self.send(self.client.patch(url.await).json(&request));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            200 => {
+                let contents = res.json::<UserResponse>().await?;
+                Ok(contents)
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// Set the password of a user
+    ///
+    /// Uses basic authentication when set with `set_authentication`, the token otherwise.
+    pub async fn set_user_password(&self, user_id: &str, password: &str) -> Result<(), error::Error> {
+        let url_format = format!("api/v2/users/{}/password", user_id);
+        let url = self.build_url(&url_format, None);
+
+        let body = PasswordRequest {
+            password: password.to_string(),
+        };
+
+        let builder = self.client.post(url.await).json(&body);
+        let res = self.send(self.legacy_auth(builder)).await?;
+
+        self.password_response(res).await
+    }
+
+    /// Change the password of the user the client is authenticated as
+    ///
+    /// InfluxDB requires basic authentication with the current password for this.
+    pub async fn set_my_password(&self, old_password: &str, new_password: &str) -> Result<(), error::Error> {
+        let me = self.get_me().await?;
+
+        let url = self.build_url("api/v2/me/password", None);
+
+        let body = PasswordRequest {
+            password: new_password.to_string(),
+        };
+
+        let request = self.client.put(url.await)
+            .basic_auth(me.name, Some(old_password))
+            .json(&body)
+            .build()?;
+        let fut = self.execute(request);
+
+        let res = fut.await?;
+        self.password_response(res).await
+    }
+
+    async fn get_user_at(&self, path: &str) -> Result<UserResponse, error::Error> {
+        let url = self.build_url(path, None);
+
+        let fut = self.send(self.client.get(url.await));
+
+        let res = fut.await?;
+        match res.status().as_u16() {
+            200 => {
+                let contents = res.json::<UserResponse>().await?;
+                Ok(contents)
+            }
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    async fn password_response(&self, res: reqwest::Response) -> Result<(), error::Error> {
+        match res.status().as_u16() {
+            204 => Ok(()),
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+}
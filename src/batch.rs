@@ -0,0 +1,304 @@
+use bytes::BytesMut;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::{Interval, MissedTickBehavior};
+
+use crate::{error, line_protocol, Client, Point, Precision};
+
+/// Options controlling when a `BatchWriter` flushes its buffer
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Flush once this many points are buffered
+    pub max_points: usize,
+    /// Flush before the serialized line protocol would exceed this many bytes
+    ///
+    /// A single point larger than this is written in a batch of its own.
+    pub max_bytes: usize,
+    /// Flush whatever is buffered at this interval, `Duration::ZERO` disables it
+    pub flush_interval: Duration,
+    /// Precision of the point timestamps
+    pub precision: Precision,
+    /// Retention policy passed along with every write
+    pub rp: Option<String>,
+    /// Number of points the channel holds before `write` waits
+    pub channel_capacity: usize,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        BatchOptions {
+            max_points: 5000,
+            max_bytes: 1024 * 1024,
+            flush_interval: Duration::from_secs(1),
+            precision: Precision::Seconds,
+            rp: None,
+            channel_capacity: 10_000,
+        }
+    }
+}
+
+impl BatchOptions {
+    /// Set the maximum number of points per batch
+    pub fn max_points(mut self, max_points: usize) -> Self {
+        self.max_points = max_points;
+        self
+    }
+
+    /// Set the maximum size of a batch in bytes
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Set the interval at which buffered points are flushed, zero to only flush on size
+    pub fn flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
+
+    /// Set the precision of the point timestamps
+    pub fn precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Set the retention policy
+    pub fn rp<T: Into<String>>(mut self, rp: T) -> Self {
+        self.rp = Some(rp.into());
+        self
+    }
+
+    /// Set the capacity of the channel feeding the writer
+    pub fn channel_capacity(mut self, channel_capacity: usize) -> Self {
+        self.channel_capacity = channel_capacity;
+        self
+    }
+}
+
+/// A batch that could not be written, handed back with its points
+#[derive(Debug)]
+pub struct BatchError {
+    /// Error returned by the write
+    pub error: error::Error,
+    /// Points that were in the failed batch
+    pub points: Vec<Point<'static>>,
+}
+
+impl BatchError {
+    /// Points InfluxDB named as rejected, or every point when the whole batch failed
+    ///
+    /// Points dropped for being outside the retention period are only counted in
+    /// `WriteError::dropped`, they cannot be told apart from the written ones.
+    pub fn rejected_points(&self) -> Vec<&Point<'static>> {
+        match self.error.write_error() {
+            Some(write_error) => write_error
+                .rejected_indices()
+                .into_iter()
+                .filter_map(|index| self.points.get(index))
+                .collect(),
+            None => self.points.iter().collect(),
+        }
+    }
+}
+
+enum Command {
+    Write(Point<'static>),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Buffers points in the background and writes them in batches
+///
+/// Must be created from within a tokio runtime.
+/// Failed batches are passed to the error callback given to `Client::batch_writer`.
+#[derive(Debug)]
+pub struct BatchWriter {
+    sender: mpsc::Sender<Command>,
+    handle: JoinHandle<()>,
+}
+
+impl BatchWriter {
+    /// Queue a point, waiting if the channel is full
+    pub async fn write(&self, point: Point<'static>) -> Result<(), error::Error> {
+        self.sender
+            .send(Command::Write(point))
+            .await
+            .map_err(|_| closed())
+    }
+
+    /// Queue multiple points
+    pub async fn write_points<T: IntoIterator<Item = Point<'static>>>(
+        &self,
+        points: T,
+    ) -> Result<(), error::Error> {
+        for point in points {
+            self.write(point).await?;
+        }
+        Ok(())
+    }
+
+    /// Write everything queued so far and wait for it to finish
+    pub async fn flush(&self) -> Result<(), error::Error> {
+        let (tx, rx) = oneshot::channel();
+        self.sender
+            .send(Command::Flush(tx))
+            .await
+            .map_err(|_| closed())?;
+        rx.await.map_err(|_| closed())
+    }
+
+    /// Drain the buffer and stop the background task
+    pub async fn close(self) -> Result<(), error::Error> {
+        drop(self.sender);
+        self.handle.await.map_err(|e| error::Error {
+            inner: error::ErrorKind::Communication(e.to_string()),
+        })
+    }
+}
+
+impl Client {
+    /// Spawn a `BatchWriter` that writes to the client's bucket
+    pub fn batch_writer<F>(&self, options: BatchOptions, on_error: F) -> BatchWriter
+    where
+        F: Fn(BatchError) + Send + Sync + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(options.channel_capacity.max(1));
+        let handle = tokio::spawn(run(self.clone(), options, receiver, on_error));
+
+        BatchWriter { sender, handle }
+    }
+}
+
+fn closed() -> error::Error {
+    error::Error {
+        inner: error::ErrorKind::Communication("Batch writer has been closed".to_string()),
+    }
+}
+
+struct Buffer {
+    line: BytesMut,
+    points: Vec<Point<'static>>,
+}
+
+impl Buffer {
+    async fn flush<F>(&mut self, client: &Client, options: &BatchOptions, on_error: &F)
+    where
+        F: Fn(BatchError),
+    {
+        if self.points.is_empty() {
+            return;
+        }
+
+        // Keeps the allocation for the next batch once the write is done with it
+        let line = self.line.split().freeze();
+        let points = std::mem::take(&mut self.points);
+
+        if let Err(error) = client
+            .write_line(line, Some(options.precision), options.rp.as_deref(), &client.retry_policy)
+            .await
+        {
+            on_error(BatchError { error, points });
+        }
+    }
+}
+
+async fn run<F>(
+    client: Client,
+    options: BatchOptions,
+    mut receiver: mpsc::Receiver<Command>,
+    on_error: F,
+) where
+    F: Fn(BatchError),
+{
+    let mut buffer = Buffer {
+        line: BytesMut::new(),
+        points: Vec::new(),
+    };
+
+    // tokio panics on a zero period, zero means no interval flushing instead
+    let mut interval = (!options.flush_interval.is_zero()).then(|| {
+        let mut interval = tokio::time::interval(options.flush_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        interval
+    });
+
+    loop {
+        tokio::select! {
+            command = receiver.recv() => match command {
+                Some(Command::Write(point)) => {
+                    let start = buffer.line.len();
+                    line_protocol::encode_point(&point, &mut buffer.line);
+
+                    // Send what was buffered before the point pushes the batch past max_bytes
+                    if buffer.line.len() > options.max_bytes && !buffer.points.is_empty() {
+                        let line = buffer.line.split_off(start);
+                        buffer.flush(&client, &options, &on_error).await;
+                        buffer.line.unsplit(line);
+                        reset(&mut interval);
+                    }
+
+                    buffer.points.push(point);
+
+                    if buffer.points.len() >= options.max_points || buffer.line.len() >= options.max_bytes {
+                        buffer.flush(&client, &options, &on_error).await;
+                        reset(&mut interval);
+                    }
+                }
+                Some(Command::Flush(done)) => {
+                    buffer.flush(&client, &options, &on_error).await;
+                    let _ = done.send(());
+                }
+                None => {
+                    buffer.flush(&client, &options, &on_error).await;
+                    break;
+                }
+            },
+            _ = tick(&mut interval) => {
+                buffer.flush(&client, &options, &on_error).await;
+            }
+        }
+    }
+}
+
+async fn tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+fn reset(interval: &mut Option<Interval>) {
+    if let Some(interval) = interval {
+        interval.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ApiError;
+    use crate::write_error;
+
+    #[test]
+    fn rejected_points_after_multi_line_string() {
+        let points = vec![
+            Point::new("log").add_field("msg", "first\nsecond"),
+            Point::new("cpu").add_field("value", 2.5),
+            Point::new("cpu").add_field("value", 3),
+        ];
+
+        let mut body = BytesMut::new();
+        line_protocol::encode_points(&points, &mut body);
+
+        let message = r#"{"message":"field type conflict: input field \"value\" on measurement \"cpu\" is type integer, already exists as type float dropped=1"}"#;
+        let error = error::Error::from_status(ApiError::parse(422, "POST", "/api/v2/write", message), None);
+
+        let batch = BatchError {
+            error: write_error::attach(error, &body),
+            points,
+        };
+        assert_eq!(batch.rejected_points(), vec![&batch.points[2]]);
+    }
+}
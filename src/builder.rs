@@ -0,0 +1,299 @@
+use reqwest::{Proxy, Url};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::session::Session;
+use crate::{error, Client, Compression, CredentialsProvider, RetryPolicy, StaticToken, WriteMode};
+
+/// Builder for a `Client` and the `reqwest::Client` underneath it
+///
+/// ```no_run
+/// use influxdb_rs::ClientBuilder;
+/// use std::time::Duration;
+/// use url::Url;
+///
+/// # async fn run() -> Result<(), influxdb_rs::Error> {
+/// let client = ClientBuilder::new(Url::parse("https://influx.lab:8086").unwrap(), "test_bucket", "test_org")
+///     .token("0123456789")
+///     .connect_timeout(Duration::from_secs(5))
+///     .timeout(Duration::from_secs(30))
+///     .lazy_org_id()
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ClientBuilder {
+    host: Url,
+    bucket: String,
+    org: String,
+    org_id: Option<String>,
+    eager_org_id: bool,
+    jwt_token: Option<String>,
+    credentials: Option<Arc<dyn CredentialsProvider>>,
+    session: Option<(String, String)>,
+    retry_policy: RetryPolicy,
+    write_mode: WriteMode,
+    compression: Compression,
+    #[cfg(feature = "gzip")]
+    accept_gzip: bool,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxies: Vec<Proxy>,
+    no_proxy: bool,
+    #[cfg(feature = "rustls-tls")]
+    root_certificates: Vec<reqwest::Certificate>,
+    #[cfg(feature = "rustls-tls")]
+    identity: Option<reqwest::Identity>,
+    #[cfg(feature = "rustls-tls")]
+    accept_invalid_certs: bool,
+}
+
+impl ClientBuilder {
+    /// Client for the bucket and org on this host, resolving the org ID on `build`
+    pub fn new<T>(host: Url, bucket: T, org: T) -> Self
+    where
+        T: Into<String>,
+    {
+        ClientBuilder {
+            host,
+            bucket: bucket.into(),
+            org: org.into(),
+            org_id: None,
+            eager_org_id: true,
+            jwt_token: None,
+            credentials: None,
+            session: None,
+            retry_policy: RetryPolicy::default(),
+            write_mode: WriteMode::default(),
+            compression: Compression::default(),
+            #[cfg(feature = "gzip")]
+            accept_gzip: false,
+            connect_timeout: None,
+            timeout: None,
+            proxies: Vec::new(),
+            no_proxy: false,
+            #[cfg(feature = "rustls-tls")]
+            root_certificates: Vec::new(),
+            #[cfg(feature = "rustls-tls")]
+            identity: None,
+            #[cfg(feature = "rustls-tls")]
+            accept_invalid_certs: false,
+        }
+    }
+
+    /// Authenticate every request with this token
+    pub fn token<T: Into<String>>(mut self, token: T) -> Self {
+        let token = token.into();
+        self.credentials = Some(Arc::new(StaticToken::new(token.clone())));
+        self.jwt_token = Some(token);
+        self
+    }
+
+    /// Ask this provider for the token of every request
+    pub fn credentials<P>(mut self, provider: P) -> Self
+    where
+        P: CredentialsProvider + 'static,
+    {
+        self.credentials = Some(Arc::new(provider));
+        self
+    }
+
+    /// Sign in with a username and password on `build` instead of using a token
+    pub fn session<T: Into<String>>(mut self, username: T, password: T) -> Self {
+        self.session = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Use this org ID instead of looking it up
+    pub fn org_id<T: Into<String>>(mut self, org_id: T) -> Self {
+        self.org_id = Some(org_id.into());
+        self
+    }
+
+    /// Look the org ID up on `build`, failing if the org cannot be found. This is the default
+    pub fn eager_org_id(mut self) -> Self {
+        self.eager_org_id = true;
+        self
+    }
+
+    /// Look the org ID up the first time a call needs it instead of on `build`
+    ///
+    /// The ID is cached after that first lookup and shared by clones of the client.
+    /// Useful for tokens that cannot read organizations.
+    pub fn lazy_org_id(mut self) -> Self {
+        self.eager_org_id = false;
+        self
+    }
+
+    /// Retry policy applied to writes and queries
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Endpoint used for writes
+    pub fn write_mode(mut self, mode: WriteMode) -> Self {
+        self.write_mode = mode;
+        self
+    }
+
+    /// Compression of write bodies
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Send `Accept-Encoding: gzip` and decompress gzipped responses, mostly query results
+    ///
+    /// Off unless set. Needs the `gzip` feature, which also turns gzip decoding on by
+    /// default for every other reqwest client in the build.
+    #[cfg(feature = "gzip")]
+    pub fn accept_gzip(mut self, accept: bool) -> Self {
+        self.accept_gzip = accept;
+        self
+    }
+
+    /// Timeout for establishing a connection
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for a whole request, from connecting until the response body is read
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Send requests through this proxy, can be called more than once
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Ignore the proxy environment variables
+    pub fn no_proxy(mut self) -> Self {
+        self.no_proxy = true;
+        self
+    }
+
+    /// Trust this CA certificate in addition to the system roots
+    #[cfg(feature = "rustls-tls")]
+    pub fn add_root_certificate(mut self, certificate: reqwest::Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Present this client certificate for mutual TLS
+    #[cfg(feature = "rustls-tls")]
+    pub fn identity(mut self, identity: reqwest::Identity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Skip verification of the server certificate
+    ///
+    /// Only meant for lab setups with self-signed certificates.
+    #[cfg(feature = "rustls-tls")]
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    /// Build the client, signing in and resolving the org ID as configured
+    pub async fn build(self) -> Result<Client, error::Error> {
+        let mut builder = reqwest::Client::builder();
+
+        #[cfg(feature = "gzip")]
+        {
+            builder = builder.gzip(self.accept_gzip);
+        }
+
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if self.no_proxy {
+            builder = builder.no_proxy();
+        }
+        for proxy in self.proxies {
+            builder = builder.proxy(proxy);
+        }
+
+        #[cfg(feature = "rustls-tls")]
+        {
+            for certificate in self.root_certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+            if let Some(identity) = self.identity {
+                builder = builder.identity(identity);
+            }
+            builder = builder.danger_accept_invalid_certs(self.accept_invalid_certs);
+        }
+
+        let mut client = Client::new_with_client(self.host, self.bucket, self.org, builder.build()?);
+        client.jwt_token = self.jwt_token;
+        client.credentials = self.credentials;
+        client.retry_policy = self.retry_policy;
+        client.write_mode = self.write_mode;
+        client.compression = self.compression;
+
+        if let Some((username, password)) = self.session {
+            client.session = Some(Arc::new(Session::new(username, password)));
+            client.signin().await?;
+        }
+
+        match self.org_id {
+            Some(org_id) => client.org_id = org_id,
+            None if self.eager_org_id => client.org_id = client.get_org_id().await?,
+            None => {}
+        }
+
+        Ok(client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn build_without_network() {
+        let client = ClientBuilder::new(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org")
+            .token("0123456789")
+            .org_id("0a")
+            .timeout(Duration::from_secs(1))
+            .no_proxy()
+            .write_mode(WriteMode::V1)
+            .compression(Compression::GzipAbove(4096))
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(client.org_id, "0a");
+        assert_eq!(client.jwt_token.as_deref(), Some("0123456789"));
+        assert_eq!(client.write_mode, WriteMode::V1);
+        assert_eq!(client.compression, Compression::GzipAbove(4096));
+        assert_eq!(client.credentials.unwrap().token().as_deref(), Some("0123456789"));
+    }
+
+    #[tokio::test]
+    async fn lazy_org_id() {
+        let client = ClientBuilder::new(Url::parse("http://localhost:1").unwrap(), "test_bucket", "test_org")
+            .lazy_org_id()
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(client.org_id, "");
+
+        // Resolved once and shared with clones, no further lookups
+        client.lazy_org_id.set("0b".to_string()).unwrap();
+        let clone = client.clone();
+        assert_eq!(clone.resolved_org_id().await.unwrap(), "0b");
+    }
+}
@@ -1,11 +1,50 @@
 use futures::prelude::*;
-use reqwest::{Client as HttpClient, Url, header};
+use reqwest::{Client as HttpClient, Request, RequestBuilder, Response, StatusCode, Url, header};
+use flate2::{write::GzEncoder, Compression as GzLevel};
 use std::{
     borrow::Borrow,
+    io::{self, Write},
+    sync::Arc,
 };
 
-use crate::{error, serialization, Point, Points, Precision, data_model};
+use crate::{error, line_protocol, write_error, Point, Points, Precision, data_model, RetryPolicy};
+use crate::api::session::Session;
+use crate::{ClientBuilder, CredentialsProvider, StaticToken};
 use serde_json::json;
+use tokio::sync::OnceCell;
+
+/// Endpoint used for writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteMode {
+    /// api/v2/write with bucket and org
+    #[default]
+    V2,
+    /// v1 compatible /write with db and rp, for InfluxDB 1.8 and DBRP mapped buckets.
+    /// Authenticates with the Basic Authentication credentials if set, the token otherwise.
+    V1,
+}
+
+/// Compression of write bodies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// Send line protocol as is
+    #[default]
+    None,
+    /// Gzip every write body
+    Gzip,
+    /// Gzip write bodies of at least this many bytes
+    GzipAbove(usize),
+}
+
+impl Compression {
+    pub(crate) fn applies(&self, len: usize) -> bool {
+        match *self {
+            Compression::None => false,
+            Compression::Gzip => true,
+            Compression::GzipAbove(threshold) => len >= threshold,
+        }
+    }
+}
 
 /// The client to influxdb
 #[derive(Debug, Clone)]
@@ -21,10 +60,22 @@ pub struct Client {
     pub org_id: String,
     /// Basic Authentication for Legacy Endpoints i.e. http://url/query (Old Endpoint) vs http://url/api/v2/query (New Endpoint)
     pub authentication: Option<(String, String)>,
-    /// Token authentication used for all interactions with the InfluxDB
+    /// Token the client was created with, see `set_credentials` for rotating tokens
     pub jwt_token: Option<String>,
     /// Used for a specifid HTTPClient
     pub client: HttpClient,
+    /// Retry policy applied to writes and queries
+    pub retry_policy: RetryPolicy,
+    /// Endpoint used for writes
+    pub write_mode: WriteMode,
+    /// Compression of write bodies
+    pub compression: Compression,
+    /// Username/password session, shared between clones
+    pub(crate) session: Option<Arc<Session>>,
+    /// Consulted for the token of every request
+    pub(crate) credentials: Option<Arc<dyn CredentialsProvider>>,
+    /// Org ID looked up on first use when `org_id` is empty, shared between clones
+    pub(crate) lazy_org_id: Arc<OnceCell<String>>,
 }
 
 impl Client {
@@ -34,32 +85,10 @@ impl Client {
     where
         T: Into<String>,
     {
-
-        let token = jwt.into();
-        let authorized = format!("Token {}", token.clone());
-
-        let mut headers = header::HeaderMap::new();
-        headers.insert("Authorization", header::HeaderValue::from_str(&authorized).unwrap());
-
-        // get a client builder
-        let httpclient = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()?;
-
-        let mut client = Client {
-            host,
-            bucket: bucket.into(),
-            org: org.into(),
-            org_id: "".to_string(),
-            authentication: None,
-            jwt_token: Some(token.clone()),
-            client: httpclient,
-        };
-
-        client.org_id = client.get_org_id().await?;
-        Ok(client)
-
-
+        ClientBuilder::new(host, bucket, org)
+            .token(jwt)
+            .build()
+            .await
     }
 
     /// Create a new influxdb client with http
@@ -68,70 +97,59 @@ impl Client {
     where
         T: Into<String>,
     {
+        ClientBuilder::new(host, bucket, org)
+            .token(jwt)
+            .lazy_org_id()
+            .build()
+            .await
+    }
 
-        let token = jwt.into();
-        let authorized = format!("Token {}", token.clone());
-
-        let mut headers = header::HeaderMap::new();
-        headers.insert("Authorization", header::HeaderValue::from_str(&authorized).unwrap());
-
-        // get a client builder
-        let httpclient = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()?;
-
-        let client = Client {
-            host,
-            bucket: bucket.into(),
-            org: org.into(),
-            org_id: "".to_string(),
-            authentication: None,
-            jwt_token: Some(token.clone()),
-            client: httpclient,
-        };
-
-        Ok(client)
-
-
+    /// Builder for timeouts, TLS, proxy and authentication options
+    #[inline] 
+    pub fn builder<T>(host: Url, bucket: T, org: T) -> ClientBuilder
+    where
+        T: Into<String>,
+    {
+        ClientBuilder::new(host, bucket, org)
     }
 
     /// Retrieves Organization ID which is represented inside InfluxDB
     #[inline] 
-    pub async fn get_org_id(&mut self) -> Result<String, error::Error> {
+    pub async fn get_org_id(&self) -> Result<String, error::Error> {
         let param = vec![("org", self.org.as_str())];
 
-        let url = self.build_url("api/v2/orgs", Some(param));
-        let fut = self.client.get(url.await).send();
+        let url = self.endpoint_url("api/v2/orgs", Some(param));
+        let orgs = self.paginate::<data_model::org::Orgs>(url);
+        futures::pin_mut!(orgs);
 
-        let res = fut.await?;
-        let status = res.status().as_u16();
-
-        match status {
-            200 => {
-                let contents = res.json::<data_model::org::Orgs>().await?;
-
-                if contents.orgs.len() == 0 {
-                    return Err(error::Error{
-                        inner: error::ErrorKind::SyntaxError("No organization found".to_string())
-                    });
-                }
-
-                let org_id = contents.orgs[0].id.clone();
-                Ok(org_id)
+        while let Some(org) = orgs.try_next().await? {
+            if org.name == self.org {
+                return Ok(org.id);
             }
-            _ => {
-                let err = res.text().await?;
+        }
 
-                Err(error::Error{
-                    inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))
-                })
-            }
+        Err(error::Error{
+            inner: error::ErrorKind::SyntaxError("No organization found".to_string())
+        })
+    }
+
+    /// Org ID of the client, looked up once if it was not resolved when building the client
+    pub(crate) async fn resolved_org_id(&self) -> Result<String, error::Error> {
+        if !self.org_id.is_empty() {
+            return Ok(self.org_id.clone());
         }
-        
+
+        self.lazy_org_id
+            .get_or_try_init(|| self.get_org_id())
+            .await
+            .cloned()
     }
 
 
     /// Create a new influxdb client with custom reqwest's client.
+    ///
+    /// With the `gzip` feature reqwest decompresses responses for every client it
+    /// builds, so `client` then sends `Accept-Encoding: gzip` unless built with `gzip(false)`.
     pub fn new_with_client<T>(host: Url, bucket: T, org: T, client: HttpClient) -> Self
     where
         T: Into<String>,
@@ -144,6 +162,12 @@ impl Client {
             authentication: None,
             jwt_token: None,
             client,
+            retry_policy: RetryPolicy::default(),
+            write_mode: WriteMode::default(),
+            compression: Compression::default(),
+            session: None,
+            credentials: None,
+            lazy_org_id: Arc::new(OnceCell::new()),
         }
     }
 
@@ -172,7 +196,43 @@ impl Client {
     where
         T: Into<String>,
     {
-        self.jwt_token = Some(token.into());
+        let token = token.into();
+        self.credentials = Some(Arc::new(StaticToken::new(token.clone())));
+        self.jwt_token = Some(token);
+        self
+    }
+
+    /// Set the provider asked for the token of every request
+    ///
+    /// Use `SharedToken`, `EnvToken` or `FileToken` to rotate tokens without
+    /// rebuilding the client.
+    #[inline] 
+    pub fn set_credentials<P>(mut self, provider: P) -> Self
+    where
+        P: CredentialsProvider + 'static,
+    {
+        self.credentials = Some(Arc::new(provider));
+        self
+    }
+
+    /// Set the retry policy used for writes and queries
+    #[inline] 
+    pub fn set_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Set the endpoint used for writes
+    #[inline] 
+    pub fn set_write_mode(mut self, mode: WriteMode) -> Self {
+        self.write_mode = mode;
+        self
+    }
+
+    /// Set the compression of write bodies
+    #[inline] 
+    pub fn set_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
         self
     }
 
@@ -187,17 +247,15 @@ impl Client {
     pub  async fn ping(&self) -> impl Future<Output = Result<bool, error::Error>> {
         let url = self.build_url("ping", None);
 
-        let resp_future = self.client.get(url.await).send().boxed();
+        let client = self.clone();
+        let builder = self.client.get(url.await);
+        let resp_future = async move { client.send(builder).await }.boxed();
         
         async move {
             let res = resp_future.await?;
-            let status = res.status().as_u16();
-            let err = res.text().await?;
-            match status{
+            match res.status().as_u16() {
                 204 => Ok(true),
-                _ => Err(error::Error{
-                    inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))
-            }),
+                _ => Err(error::Error::from_response(res).await),
             }
         }
         
@@ -208,7 +266,7 @@ impl Client {
     pub async fn get_version(&self) ->  Result<String, error::Error>{
         let url = self.build_url("ping", None);
 
-    let resp_future = self.client.get(url.await).send().boxed();
+    let resp_future = self.send(self.client.get(url.await)).boxed();
     
         let res = resp_future.await?;
         let status = res.status().as_u16();
@@ -227,13 +285,7 @@ impl Client {
                     }
                 }
             },
-            _ => {
-                let err = res.text().await?;
-
-                Err(error::Error{
-                    inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))
-            })
-            }
+            _ => Err(error::Error::from_response(res).await),
         }
 
     }
@@ -250,52 +302,81 @@ impl Client {
     }
 
     /// Write multiple points to the database
+    ///
+    /// When InfluxDB rejects some of the lines the error is `ErrorKind::PartialWrite`,
+    /// listing the indices of the rejected points. The other points were written.
     pub async fn write_points<'a, T: IntoIterator<Item = impl Borrow<Point<'a>>>>(
         &self,
         points: T,
         precision: Option<Precision>,
         rp: Option<&str>,
     ) -> Result<(), error::Error> {
-        let line = serialization::line_serialization(points);
+        self.write_points_with_retry(points, precision, rp, &self.retry_policy).await
+    }
+
+    /// Write multiple points to the database, overriding the client's retry policy
+    pub async fn write_points_with_retry<'a, T: IntoIterator<Item = impl Borrow<Point<'a>>>>(
+        &self,
+        points: T,
+        precision: Option<Precision>,
+        rp: Option<&str>,
+        retry: &RetryPolicy,
+    ) -> Result<(), error::Error> {
+        let mut line = bytes::BytesMut::new();
+        line_protocol::encode_points(points, &mut line);
 
-        let mut param = vec![("bucket", self.bucket.as_str()), ("org", self.org.as_str())];
+        self.write_line(line.freeze(), precision, rp, retry).await
+    }
 
-        match precision {
-            Some(ref t) => param.push(("precision", t.to_str())),
-            None => param.push(("precision", "s")),
+    /// Write an already serialized line protocol body to the database
+    pub(crate) async fn write_line(
+        &self,
+        line: bytes::Bytes,
+        precision: Option<Precision>,
+        rp: Option<&str>,
+        retry: &RetryPolicy,
+    ) -> Result<(), error::Error> {
+        let precision = precision.unwrap_or(Precision::Seconds);
+
+        let (path, mut param) = match self.write_mode {
+            WriteMode::V2 => ("api/v2/write", vec![
+                ("bucket", self.bucket.as_str()),
+                ("org", self.org.as_str()),
+                ("precision", precision.to_str()),
+            ]),
+            WriteMode::V1 => ("write", vec![
+                ("db", self.bucket.as_str()),
+                ("precision", precision.to_v1_str()),
+            ]),
         };
 
         if let Some(t) = rp {
             param.push(("rp", t))
         }
 
-        let url = self.build_url("api/v2/write", Some(param));
-        let fut = self.client.post(url.await).body(line).send();
-
-        let res = fut.await?;
-        let status = res.status().as_u16();
-        let err = res.text().await?;
+        let url = self.build_url(path, Some(param)).await;
+        let gzipped = self.compression.applies(line.len());
+        let body = if gzipped {
+            gzip(&line).map_err(|e| error::Error {
+                inner: error::ErrorKind::Compression(e.to_string()),
+            })?
+        } else {
+            line.clone()
+        };
 
-        match status {
+        let res = retry.send(self, || {
+            let mut builder = self.client.post(url.clone()).body(body.clone());
+            if gzipped {
+                builder = builder.header(header::CONTENT_ENCODING, "gzip");
+            }
+            match self.write_mode {
+                WriteMode::V2 => builder,
+                WriteMode::V1 => self.legacy_auth(builder),
+            }
+        }).await?;
+        match res.status().as_u16() {
             204 => Ok(()),
-            400 => Err(error::Error {
-                inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))
-            }),
-            401 | 403 => Err(error::Error {
-                inner: error::ErrorKind::InvalidCredentials(
-                "Invalid authentication credentials.".to_string(),
-            )}),
-            404 => Err(error::Error{
-                inner: error::ErrorKind::DataBaseDoesNotExist(
-                serialization::conversion(&err),
-            )}),
-            500 => Err(error::Error{
-                inner: error::ErrorKind::RetentionPolicyDoesNotExist(err)}),
-            status => Err(error::Error{
-                inner: error::ErrorKind::Unknown(format!(
-                "Received status code {}",
-                status
-            ))}),
+            _ => Err(write_error::attach(error::Error::from_response(res).await, &line)),
         }
     }
 
@@ -311,7 +392,7 @@ impl Client {
 
         let url = self.build_url("api/v2/delete", Some(param));
 
-        let measure = format!("_measurement=\"{}\"", measurement.clone()).as_str().to_owned();
+        let measure = format!("_measurement=\"{}\"", measurement).as_str().to_owned();
 
         let body = data_model::query::DeleteQuery{
             predicate: measure,
@@ -321,65 +402,23 @@ impl Client {
 
         let builder = self.client.post(url.await).body(json!(body).to_string());
 
-        let resp_future = builder.send();
+        let resp_future = self.send(builder);
 
         let res = resp_future.await?;
         match res.status().as_u16() {
             204 => Ok(()),
-            400 => {
-                let err = res.text().await?;
-
-                Err(error::Error{
-                    inner: error::ErrorKind::SyntaxError(serialization::conversion(
-                    &err,
-                ))})
-            }
-            401 | 403 => Err(error::Error{
-                inner: error::ErrorKind::InvalidCredentials(
-                "Invalid authentication credentials.".to_string(),
-            )}),
-            _ => Err(error::Error{
-                inner: error::ErrorKind::Unknown("There is something wrong".to_string())
-            }),
+            _ => Err(error::Error::from_response(res).await),
         }
     }
 
     /// Create a new database in InfluxDB.
     pub async fn create_database(&self, dbname: &str) -> Result<(), error::Error> {
+        let org_id = self.resolved_org_id().await?;
+        let request = data_model::bucket::CreateBucketRequest::new(org_id.as_str(), dbname)
+            .schema_type(data_model::bucket::SchemaType::Implicit);
 
-        let retention_rules = data_model::retention_rules::RetentionRules{
-            every_seconds: 0,
-            shard_group_duration_seconds: 0,
-            retention_type: "expire".to_string(),
-        };
-
-        let body = data_model::bucket::Bucket{
-            name: dbname.to_string(),
-            org_id: self.org_id.clone(),
-            retention_rules: vec![retention_rules],
-            description: "".to_string(),
-            rp: None,
-            schema_type: "Implicit".to_string(),
-
-        };
-
-        let post_body = json!(body).to_string();
-
-        let url = self.build_url("api/v2/buckets", None);
-        let fut = self.client.post(url.await).body(post_body).send();
-
-        let res = fut.await?;
-        let status = res.status().as_u16();
-        let err = res.text().await?;
-
-        match status {
-            201 => Ok(()),
-            422 => Err(error::Error{
-                inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))}),
-            _ => Err(error::Error{
-                inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))
-            })
-        }
+        self.create_bucket(request).await?;
+        Ok(())
     }
 
 
@@ -387,28 +426,15 @@ impl Client {
     /// Get bucket id from InfluxDB 
     /// Provide name and get internal ID
     pub async fn get_bucket_id(&self, bucket_name: &str) -> Result<String, error::Error> {
-        let param = vec![("name", bucket_name)];
-
-        let url = self.build_url("api/v2/buckets", Some(param));
-        let fut = self.client.get(url.await).send();
-
-        let res = fut.await?;
-        let status = res.status().as_u16();
-
-        match status {
-            200 => {
-                let contents = res.json::<data_model::bucket::Buckets>().await?;
-                let bucket_id = contents.buckets[0].id.clone();
-                Ok(bucket_id)
-            }
-            _ => {
-                let err = res.text().await?;
-
-                Err(error::Error{
-                    inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))})
-            }
+        let filter = data_model::bucket::BucketFilter::default().name(bucket_name);
+        let buckets = self.buckets_stream(&filter, &data_model::links::PageOptions::default());
+        futures::pin_mut!(buckets);
+
+        match buckets.try_next().await? {
+            Some(bucket) => Ok(bucket.id),
+            None => Err(error::Error{
+                inner: error::ErrorKind::DataBaseDoesNotExist(format!("Bucket {} not found", bucket_name))})
         }
-
     }
 
     /// Drop a database from InfluxDB.
@@ -422,18 +448,74 @@ impl Client {
         let id = format!("api/v2/buckets/{}", id);
 
         let url = client.build_url(&id, None);
-        let fut = client.client.delete(url.await).send();
+        let fut = client.send(client.client.delete(url.await));
 
         let res = fut.await?;
-        let status = res.status().as_u16();
-        let err = res.text().await?;
-
-        match status {
+        match res.status().as_u16() {
             204 => Ok(()),
-            404 => Err(error::Error{
-                inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))}),
-            _ => Err(error::Error{
-                inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))})
+            _ => Err(error::Error::from_response(res).await),
+        }
+    }
+
+    /// Authenticate a request to a v1 compatible endpoint
+    /// Basic Authentication when set, Token Authentication otherwise
+    pub(crate) fn legacy_auth(&self, builder: RequestBuilder) -> RequestBuilder {
+        match self.authentication {
+            Some((ref user, ref passwd)) => builder.basic_auth(user, Some(passwd)),
+            // `send` adds the token
+            None => builder,
+        }
+    }
+
+    /// Send a request with the client's credentials
+    ///
+    /// Requests without an `Authorization` header get the token of the credentials
+    /// provider, and the session cookie if the client signed in. A request rejected
+    /// with 401 because the session expired is sent again after signing in anew.
+    /// If that fails the 401 response is returned.
+    pub(crate) async fn send(&self, builder: RequestBuilder) -> Result<Response, reqwest::Error> {
+        let mut request = builder.build()?;
+        self.authorize(&mut request);
+
+        let session = match self.session {
+            Some(ref session) => session,
+            None => return self.execute(request).await,
+        };
+
+        let retry = request.try_clone();
+        let signed_in = session.attach(&mut request).await;
+        let res = self.execute(request).await?;
+
+        if res.status() != StatusCode::UNAUTHORIZED || !signed_in {
+            return Ok(res);
+        }
+
+        match retry {
+            Some(mut retry) if self.signin().await.is_ok() => {
+                session.attach(&mut retry).await;
+                self.execute(retry).await
+            }
+            _ => Ok(res),
+        }
+    }
+
+    /// Execute the request, remembering its method for error reporting
+    pub(crate) async fn execute(&self, request: Request) -> Result<Response, reqwest::Error> {
+        let method = request.method().clone();
+        let mut res = self.client.execute(request).await?;
+        res.extensions_mut().insert(error::RequestMethod(method));
+        Ok(res)
+    }
+
+    fn authorize(&self, request: &mut Request) {
+        if request.headers().contains_key(header::AUTHORIZATION) {
+            return;
+        }
+
+        let token = self.credentials.as_ref().and_then(|provider| provider.token());
+
+        if let Some(value) = token.and_then(|t| header::HeaderValue::from_str(&format!("Token {}", t)).ok()) {
+            request.headers_mut().insert(header::AUTHORIZATION, value);
         }
     }
 
@@ -441,6 +523,10 @@ impl Client {
     /// No Basic Authentication
     #[inline] 
     pub async fn build_url(&self, key: &str, param: Option<Vec<(&str, &str)>>) -> Url {
+        self.endpoint_url(key, param)
+    }
+
+    pub(crate) fn endpoint_url(&self, key: &str, param: Option<Vec<(&str, &str)>>) -> Url {
         let url = self.host.join(key).unwrap();
 
         if let Some(param) = param {
@@ -451,29 +537,23 @@ impl Client {
     }
 
     /// connecting for default database `test` and host `http://localhost:8086`
-    pub fn default() -> impl Future<Output = Result<Self, error::Error>> {
-        async {
-            Client::new(Url::parse("http://localhost:8086").unwrap(), "test", "test", "00000000").await
-        }
+    #[allow(clippy::should_implement_trait)]
+    pub async fn default() -> Result<Self, error::Error> {
+        Client::new(Url::parse("http://localhost:8086").unwrap(), "test", "test", "00000000").await
     }
 
     ///Create a new user in InfluxDB.
     pub async fn create_new_user(&self, name: &str, status: data_model::user::Status ) -> Result<data_model::user::UserResponse, error::Error> {
         let url = self.build_url("api/v2/users", None);
 
-        let json_status = match status {
-            data_model::user::Status::Active => "active",
-            data_model::user::Status::Inactive => "inactive",
-        };
-        
         let body = data_model::user::CreateUser{
             name: name.to_string(),
-            status: json_status.to_string(),
+            status,
         };
 
         let post_body = json!(body).to_string();
 
-        let fut = self.client.post(url.await).body(post_body).send();
+        let fut = self.send(self.client.post(url.await).body(post_body));
 
         let res = fut.await?;
         let res_status = res.status().as_u16();
@@ -483,18 +563,7 @@ impl Client {
                 let contents = res.json::<data_model::user::UserResponse>().await?;
                 Ok(contents)
             },
-            400 => {
-                let err = res.text().await?;
-
-                return Err(error::Error{
-                    inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))})
-            }
-            _ => {
-                let err = res.text().await?;
-
-                return Err(error::Error{
-                    inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))})
-            }
+            _ => Err(error::Error::from_response(res).await),
         }
     }
 
@@ -502,22 +571,17 @@ impl Client {
     pub async fn create_authorization(&self, user_id: Option<String>, org_id: &str, permissions: Vec<data_model::authorization::AuthPermissions>, status: data_model::user::Status, description: &str) -> Result<data_model::authorization::AuthorizationResponse, error::Error> {
         let url = self.build_url("api/v2/authorizations", None);
 
-        let json_status = match status {
-            data_model::user::Status::Active => "active",
-            data_model::user::Status::Inactive => "inactive",
-        };
-
         let body = data_model::authorization::CreateAuthorization{
             org_id: org_id.to_string(),
             user_id,
             permissions,
-            status: json_status.to_string(),
+            status,
             description: description.to_string(),
         };
 
         let post_body = json!(body).to_string();
 
-        let fut = self.client.post(url.await).body(post_body).send();
+        let fut = self.send(self.client.post(url.await).body(post_body));
 
         let res = fut.await?;
         let res_status = res.status().as_u16();
@@ -527,48 +591,13 @@ impl Client {
                 let contents = res.json::<data_model::authorization::AuthorizationResponse>().await?;
                 Ok(contents)
             },
-            400 => {
-                let err = res.text().await?;
-
-                return Err(error::Error{
-                    inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))})
-            }
-            _ => {
-                let err = res.text().await?;
-
-                return Err(error::Error{
-                    inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))})
-            }
+            _ => Err(error::Error::from_response(res).await),
         }
     }
 
     /// List Users
-    pub async fn list_users(&self) -> Result<Vec<data_model::user::UserResponse>, error::Error> {
-        let url = self.build_url("api/v2/users", None);
-
-        let fut = self.client.get(url.await).send();
-
-        let res = fut.await?;
-        let res_status = res.status().as_u16();
-
-        match res_status {
-            200 =>  {
-                let contents = res.json::<data_model::user::ListUserResponse>().await?;
-                Ok(contents.users)
-            },
-            400 => {
-                let err = res.text().await?;
-
-                return Err(error::Error{
-                    inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))})
-            }
-            _ => {
-                let err = res.text().await?;
-
-                return Err(error::Error{
-                    inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))})
-            }
-        }
+    pub async fn list_users(&self, filter: &data_model::user::UserFilter) -> Result<Vec<data_model::user::UserResponse>, error::Error> {
+        self.users_stream(filter, &data_model::links::PageOptions::default()).try_collect().await
     }
 
     /// Delete A User
@@ -576,7 +605,7 @@ impl Client {
         let url_format = format!("api/v2/users/{}", user_id);
         let url = self.build_url(&url_format, None);
 
-        let fut = self.client.delete(url.await).send();
+        let fut = self.send(self.client.delete(url.await));
 
         let res = fut.await?;
         let res_status = res.status().as_u16();
@@ -585,21 +614,43 @@ impl Client {
             204 =>  {
                 Ok(())
             },
-            400 => {
-                let err = res.text().await?;
-
-                return Err(error::Error{
-                    inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))})
-            }
-            _ => {
-                let err = res.text().await?;
-
-                return Err(error::Error{
-                    inner: error::ErrorKind::SyntaxError(serialization::conversion(&err))})
-            }
+            _ => Err(error::Error::from_response(res).await),
         }
     }
 
 
     
 }
+
+/// Gzip a write body
+fn gzip(data: &[u8]) -> Result<bytes::Bytes, io::Error> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(data.len() / 4), GzLevel::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn compression_threshold() {
+        assert!(!Compression::None.applies(1 << 20));
+        assert!(Compression::Gzip.applies(0));
+        assert!(!Compression::GzipAbove(1024).applies(1023));
+        assert!(Compression::GzipAbove(1024).applies(1024));
+    }
+
+    #[test]
+    fn gzip_round_trip() {
+        let line = "cpu,host=a value=1i 1\n".repeat(100);
+        let body = gzip(line.as_bytes()).unwrap();
+        assert!(body.len() < line.len());
+
+        let mut decoded = String::new();
+        GzDecoder::new(&body[..]).read_to_string(&mut decoded).unwrap();
+        assert_eq!(decoded, line);
+    }
+}
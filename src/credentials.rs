@@ -0,0 +1,177 @@
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::SystemTime;
+
+/// Source of the token sent with each request
+///
+/// The client asks the provider for a token every time it sends a request,
+/// so a provider can swap tokens at runtime without rebuilding the client.
+pub trait CredentialsProvider: fmt::Debug + Send + Sync {
+    /// Token for the next request, `None` to send no `Authorization` header
+    fn token(&self) -> Option<String>;
+}
+
+/// The same token for every request
+#[derive(Clone)]
+pub struct StaticToken(String);
+
+impl StaticToken {
+    /// Always hand out this token
+    pub fn new<T: Into<String>>(token: T) -> Self {
+        StaticToken(token.into())
+    }
+}
+
+impl fmt::Debug for StaticToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StaticToken(..)")
+    }
+}
+
+impl CredentialsProvider for StaticToken {
+    fn token(&self) -> Option<String> {
+        Some(self.0.clone())
+    }
+}
+
+/// Token that can be replaced through any clone of the handle
+///
+/// ```
+/// use influxdb_rs::SharedToken;
+///
+/// let token = SharedToken::new("first");
+/// let handle = token.clone();
+/// handle.set("rotated");
+/// ```
+#[derive(Clone)]
+pub struct SharedToken(Arc<RwLock<String>>);
+
+impl SharedToken {
+    /// Start out with this token
+    pub fn new<T: Into<String>>(token: T) -> Self {
+        SharedToken(Arc::new(RwLock::new(token.into())))
+    }
+
+    /// Replace the token used by all following requests
+    pub fn set<T: Into<String>>(&self, token: T) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = token.into();
+    }
+}
+
+impl fmt::Debug for SharedToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedToken(..)")
+    }
+}
+
+impl CredentialsProvider for SharedToken {
+    fn token(&self) -> Option<String> {
+        Some(self.0.read().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+}
+
+/// Token read from an environment variable on every request
+#[derive(Debug, Clone)]
+pub struct EnvToken {
+    var: String,
+}
+
+impl EnvToken {
+    /// Read the token from this variable, e.g. `INFLUX_TOKEN`
+    pub fn new<T: Into<String>>(var: T) -> Self {
+        EnvToken { var: var.into() }
+    }
+}
+
+impl CredentialsProvider for EnvToken {
+    fn token(&self) -> Option<String> {
+        std::env::var(&self.var).ok().filter(|token| !token.is_empty())
+    }
+}
+
+/// Token read from a file, read again whenever the file's modification time changes
+///
+/// Surrounding whitespace is trimmed. If the file cannot be read the last token is kept.
+#[derive(Debug)]
+pub struct FileToken {
+    path: PathBuf,
+    cache: Mutex<Option<(SystemTime, String)>>,
+}
+
+impl FileToken {
+    /// Read the token from this file
+    pub fn new<T: Into<PathBuf>>(path: T) -> Self {
+        FileToken {
+            path: path.into(),
+            cache: Mutex::new(None),
+        }
+    }
+}
+
+impl CredentialsProvider for FileToken {
+    fn token(&self) -> Option<String> {
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+
+        let modified = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+
+        let stale = match (&*cache, modified) {
+            (Some((cached, _)), Some(modified)) => *cached != modified,
+            (None, Some(_)) => true,
+            (_, None) => false,
+        };
+
+        if stale {
+            if let (Ok(token), Some(modified)) = (fs::read_to_string(&self.path), modified) {
+                *cache = Some((modified, token.trim().to_string()));
+            }
+        }
+
+        cache.as_ref().map(|(_, token)| token.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn shared_token_rotates() {
+        let token = SharedToken::new("first");
+        let provider: Arc<dyn CredentialsProvider> = Arc::new(token.clone());
+
+        assert_eq!(provider.token().as_deref(), Some("first"));
+        token.set("second");
+        assert_eq!(provider.token().as_deref(), Some("second"));
+    }
+
+    #[test]
+    fn env_token() {
+        let provider = EnvToken::new("INFLUXDB_RS_TEST_TOKEN");
+        assert_eq!(provider.token(), None);
+
+        std::env::set_var("INFLUXDB_RS_TEST_TOKEN", "from-env");
+        assert_eq!(provider.token().as_deref(), Some("from-env"));
+        std::env::remove_var("INFLUXDB_RS_TEST_TOKEN");
+    }
+
+    #[test]
+    fn file_token_rereads_on_change() {
+        let path = std::env::temp_dir().join(format!("influxdb_rs_token_{}", std::process::id()));
+        fs::write(&path, "first\n").unwrap();
+
+        let provider = FileToken::new(&path);
+        assert_eq!(provider.token().as_deref(), Some("first"));
+
+        fs::write(&path, "second\n").unwrap();
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+        assert_eq!(provider.token().as_deref(), Some("second"));
+
+        // The last token is kept while the file is missing
+        fs::remove_file(&path).unwrap();
+        assert_eq!(provider.token().as_deref(), Some("second"));
+    }
+}
@@ -1,5 +1,7 @@
 /// Authorization Data Model
 use serde::{Serialize, Deserialize};
+use crate::data_model::links::Links;
+use crate::data_model::user::Status;
 
 /// Create a new authorization
 #[derive(Serialize, Deserialize, Debug)]
@@ -12,34 +14,168 @@ pub struct CreateAuthorization {
     #[serde(rename = "orgID")]
     pub org_id: String,
     /// Status of the authorization
-    pub status: String,
+    pub status: Status,
     /// User Id
+    #[serde(rename = "userID", skip_serializing_if = "Option::is_none")]
     pub user_id: Option<String>,
 }
 
+/// Action a permission grants
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    /// Read
+    Read,
+    /// Write
+    Write,
+}
+
 /// Authorization Permissions
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct AuthPermissions {
     /// Action of the authorization
-    pub action: String,
+    pub action: Action,
     /// Resource of the authorization
     pub resource: AuthResource,
 }
 
+/// Builder style name for `AuthPermissions`
+///
+/// ```
+/// use influxdb_rs::data_model::authorization::{AuthResourceType, Permission};
+///
+/// let permission = Permission::read(AuthResourceType::Bucket).bucket("0a").org("01");
+/// ```
+pub type Permission = AuthPermissions;
+
+/// Resource types an all-access token is granted read and write on
+const ALL_ACCESS_TYPES: [AuthResourceType; 22] = [
+    AuthResourceType::Authorizations,
+    AuthResourceType::Bucket,
+    AuthResourceType::Dashboards,
+    AuthResourceType::Orgs,
+    AuthResourceType::Sources,
+    AuthResourceType::Tasks,
+    AuthResourceType::Telegrafs,
+    AuthResourceType::Users,
+    AuthResourceType::Variables,
+    AuthResourceType::Scrapers,
+    AuthResourceType::Secrets,
+    AuthResourceType::Labels,
+    AuthResourceType::Views,
+    AuthResourceType::Documents,
+    AuthResourceType::NotificationsRules,
+    AuthResourceType::NotificationEndpoints,
+    AuthResourceType::Checks,
+    AuthResourceType::DBRP,
+    AuthResourceType::Notebooks,
+    AuthResourceType::Annotations,
+    AuthResourceType::Remotes,
+    AuthResourceType::Replications,
+];
+
+impl AuthPermissions {
+    /// Permission for the action on every resource of the type
+    pub fn new(action: Action, resource_type: AuthResourceType) -> Self {
+        AuthPermissions {
+            action,
+            resource: AuthResource {
+                r#type: resource_type,
+                id: None,
+                name: None,
+                org_id: None,
+                org: None,
+            },
+        }
+    }
+
+    /// Read permission on every resource of the type
+    pub fn read(resource_type: AuthResourceType) -> Self {
+        Self::new(Action::Read, resource_type)
+    }
+
+    /// Write permission on every resource of the type
+    pub fn write(resource_type: AuthResourceType) -> Self {
+        Self::new(Action::Write, resource_type)
+    }
+
+    /// Restrict the permission to the resource with this ID
+    pub fn id<T: Into<String>>(mut self, id: T) -> Self {
+        self.resource.id = Some(id.into());
+        self
+    }
+
+    /// Restrict the permission to the bucket with this ID
+    pub fn bucket<T: Into<String>>(self, bucket_id: T) -> Self {
+        self.id(bucket_id)
+    }
+
+    /// Restrict the permission to the resource with this name
+    pub fn name<T: Into<String>>(mut self, name: T) -> Self {
+        self.resource.name = Some(name.into());
+        self
+    }
+
+    /// Restrict the permission to resources of the org with this ID
+    pub fn org<T: Into<String>>(mut self, org_id: T) -> Self {
+        self.resource.org_id = Some(org_id.into());
+        self
+    }
+
+    /// Read and write on everything in the org, like `influx auth create --all-access`
+    pub fn all_access(org_id: &str) -> Vec<Self> {
+        ALL_ACCESS_TYPES
+            .iter()
+            .flat_map(|resource_type| {
+                let permission = match resource_type {
+                    // Users are not scoped to an org
+                    AuthResourceType::Users => Self::read(AuthResourceType::Users),
+                    // The org itself is addressed by its ID
+                    AuthResourceType::Orgs => Self::read(AuthResourceType::Orgs).id(org_id),
+                    _ => Self::read(resource_type.clone()).org(org_id),
+                };
+
+                let mut write = permission.clone();
+                write.action = Action::Write;
+
+                [permission, write]
+            })
+            .collect()
+    }
+
+    /// Read and write on a single bucket
+    pub fn read_write_bucket(org_id: &str, bucket_id: &str) -> Vec<Self> {
+        vec![
+            Self::read(AuthResourceType::Bucket).bucket(bucket_id).org(org_id),
+            Self::write(AuthResourceType::Bucket).bucket(bucket_id).org(org_id),
+        ]
+    }
+}
+
 /// Authorization Resource
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct AuthResource {
     /// Type of the authorization
-    pub r#type: String,
-    /// Org ID of the authorization
+    pub r#type: AuthResourceType,
+    /// ID of a single resource, all resources of the type if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Name of a single resource
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Org ID the resources belong to
+    #[serde(rename = "orgID", skip_serializing_if = "Option::is_none")]
     pub org_id: Option<String>,
-    /// Bucket ID of the authorization
-    pub bucket_id: Option<String>,
+    /// Org name the resources belong to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub org: Option<String>,
 }
 
 /// Authorization Resource Type
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AuthResourceType {
+    /// Authorizations
+    Authorizations,
     /// Bucket
     Bucket,
     /// Organization
@@ -92,12 +228,15 @@ pub enum AuthResourceType {
     Functions,
     /// Subscriptions
     Subscriptions,
+    /// Resource type this client does not know about
+    Other(String),
 }
 
 /// Impl Display for AuthResourceType
 impl std::fmt::Display for AuthResourceType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            AuthResourceType::Authorizations => write!(f, "authorizations"),
             AuthResourceType::Bucket => write!(f, "buckets"),
             AuthResourceType::Organization => write!(f, "orgs"),
             AuthResourceType::Dashboards => write!(f, "dashboards"),
@@ -113,7 +252,7 @@ impl std::fmt::Display for AuthResourceType {
             AuthResourceType::NotificationsRules => write!(f, "notificationRules"),
             AuthResourceType::NotificationEndpoints => write!(f, "notificationEndpoints"),
             AuthResourceType::Checks => write!(f, "checks"),
-            AuthResourceType::DBRP => write!(f, "dbrps"),
+            AuthResourceType::DBRP => write!(f, "dbrp"),
             AuthResourceType::Annotations => write!(f, "annotations"),
             AuthResourceType::Sources => write!(f, "sources"),
             AuthResourceType::Scrapers => write!(f, "scrapers"),
@@ -124,18 +263,70 @@ impl std::fmt::Display for AuthResourceType {
             AuthResourceType::Flows => write!(f, "flows"),
             AuthResourceType::Functions => write!(f, "functions"),
             AuthResourceType::Subscriptions => write!(f, "subscriptions"),
+            AuthResourceType::Other(other) => write!(f, "{}", other),
         }
     }
 }
 
+impl std::str::FromStr for AuthResourceType {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let resource_type = match s {
+            "authorizations" => AuthResourceType::Authorizations,
+            "buckets" => AuthResourceType::Bucket,
+            "dashboards" => AuthResourceType::Dashboards,
+            "orgs" => AuthResourceType::Orgs,
+            "tasks" => AuthResourceType::Tasks,
+            "telegrafs" => AuthResourceType::Telegrafs,
+            "users" => AuthResourceType::Users,
+            "variables" => AuthResourceType::Variables,
+            "secrets" => AuthResourceType::Secrets,
+            "labels" => AuthResourceType::Labels,
+            "views" => AuthResourceType::Views,
+            "documents" => AuthResourceType::Documents,
+            "notificationRules" => AuthResourceType::NotificationsRules,
+            "notificationEndpoints" => AuthResourceType::NotificationEndpoints,
+            "checks" => AuthResourceType::Checks,
+            "dbrp" | "dbrps" => AuthResourceType::DBRP,
+            "annotations" => AuthResourceType::Annotations,
+            "sources" => AuthResourceType::Sources,
+            "scrapers" => AuthResourceType::Scrapers,
+            "notebooks" => AuthResourceType::Notebooks,
+            "remotes" => AuthResourceType::Remotes,
+            "replications" => AuthResourceType::Replications,
+            "instance" => AuthResourceType::Instance,
+            "flows" => AuthResourceType::Flows,
+            "functions" => AuthResourceType::Functions,
+            "subscriptions" => AuthResourceType::Subscriptions,
+            other => AuthResourceType::Other(other.to_string()),
+        };
+        Ok(resource_type)
+    }
+}
+
+impl Serialize for AuthResourceType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for AuthResourceType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(s.parse().unwrap_or_else(|e| match e {}))
+    }
+}
+
 
 /// Authorization Response
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AuthorizationResponse {
     /// Description
+    #[serde(default)]
     pub description: String,
     /// Status
-    pub status: String,
+    pub status: Status,
     /// Created At
     #[serde(rename = "createdAt")]
     pub created_at: String,
@@ -170,4 +361,172 @@ pub struct AuthorizationLinks {
     pub authorization_self: String,
     /// User
     pub user: String,
-}
\ No newline at end of file
+}
+
+/// List of Authorizations from api/v2/authorizations
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Authorizations {
+    /// Links
+    #[serde(default)]
+    pub links: Links,
+    /// Authorizations
+    pub authorizations: Vec<AuthorizationResponse>,
+}
+
+/// Filter for listing authorizations through api/v2/authorizations
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct AuthorizationFilter {
+    /// User name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// User ID
+    #[serde(rename = "userID", skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// Org name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub org: Option<String>,
+    /// Org ID
+    #[serde(rename = "orgID", skip_serializing_if = "Option::is_none")]
+    pub org_id: Option<String>,
+    /// Token value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl AuthorizationFilter {
+    /// Only authorizations of the user with this name
+    pub fn user<T: Into<String>>(mut self, user: T) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Only authorizations of the user with this ID
+    pub fn user_id<T: Into<String>>(mut self, user_id: T) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Only authorizations of the org with this name
+    pub fn org<T: Into<String>>(mut self, org: T) -> Self {
+        self.org = Some(org.into());
+        self
+    }
+
+    /// Only authorizations of the org with this ID
+    pub fn org_id<T: Into<String>>(mut self, org_id: T) -> Self {
+        self.org_id = Some(org_id.into());
+        self
+    }
+
+    /// Only the authorization with this token
+    pub fn token<T: Into<String>>(mut self, token: T) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub(crate) fn params(&self) -> Vec<(&str, &str)> {
+        let mut params = Vec::new();
+        if let Some(ref user) = self.user {
+            params.push(("user", user.as_str()));
+        }
+        if let Some(ref user_id) = self.user_id {
+            params.push(("userID", user_id.as_str()));
+        }
+        if let Some(ref org) = self.org {
+            params.push(("org", org.as_str()));
+        }
+        if let Some(ref org_id) = self.org_id {
+            params.push(("orgID", org_id.as_str()));
+        }
+        if let Some(ref token) = self.token {
+            params.push(("token", token.as_str()));
+        }
+        params
+    }
+}
+
+/// Request to change an authorization, unset fields are left untouched
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct UpdateAuthorizationRequest {
+    /// New status
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    /// New description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl UpdateAuthorizationRequest {
+    /// Activate or deactivate the token
+    pub fn status(mut self, status: Status) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Change the description
+    pub fn description<T: Into<String>>(mut self, description: T) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn filter_params() {
+        let filter = AuthorizationFilter::default().user_id("0a").org("test_org");
+        assert_eq!(filter.params(), vec![("userID", "0a"), ("org", "test_org")]);
+    }
+
+    #[test]
+    fn serialize_permission() {
+        let permission = Permission::write(AuthResourceType::Bucket).bucket("0a").org("01");
+        assert_eq!(serde_json::to_value(&permission).unwrap(), json!({
+            "action": "write",
+            "resource": {"type": "buckets", "id": "0a", "orgID": "01"}
+        }));
+
+        let parsed: AuthPermissions = serde_json::from_value(json!({
+            "action": "read",
+            "resource": {"type": "flightSQL", "orgID": "01", "org": "test_org"}
+        })).unwrap();
+        assert_eq!(parsed.resource.r#type, AuthResourceType::Other("flightSQL".to_string()));
+    }
+
+    #[test]
+    fn serialize_create() {
+        let mut create = CreateAuthorization {
+            description: "read bucket".to_string(),
+            permissions: Vec::new(),
+            org_id: "01".to_string(),
+            status: Status::Active,
+            user_id: Some("0a".to_string()),
+        };
+        assert_eq!(serde_json::to_value(&create).unwrap()["userID"], json!("0a"));
+
+        create.user_id = None;
+        assert!(serde_json::to_value(&create).unwrap().get("userID").is_none());
+    }
+
+    #[test]
+    fn all_access_permissions() {
+        let permissions = AuthPermissions::all_access("01");
+        assert_eq!(permissions.len(), 2 * ALL_ACCESS_TYPES.len());
+
+        let orgs = permissions.iter().find(|p| p.resource.r#type == AuthResourceType::Orgs).unwrap();
+        assert_eq!(orgs.resource.id.as_deref(), Some("01"));
+        assert!(orgs.resource.org_id.is_none());
+
+        let buckets = AuthPermissions::read_write_bucket("01", "0a");
+        assert_eq!(buckets.iter().map(|p| p.action).collect::<Vec<_>>(), vec![Action::Read, Action::Write]);
+    }
+
+    #[test]
+    fn serialize_update() {
+        let update = UpdateAuthorizationRequest::default().status(Status::Inactive);
+        assert_eq!(serde_json::to_value(&update).unwrap(), json!({"status": "inactive"}));
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Serialize, Deserialize};
+use std::time::Duration;
 use crate::data_model::{labels::Labels, links::BucketLinks, retention_rules::RetentionRules, links::Links};
 
 /// Multiple Buckets Struct, used when retrieving list of all buckets from api/v2/buckets
@@ -73,3 +74,229 @@ pub struct Bucket{
     #[serde(rename = "schemaType")]
     pub schema_type: String,
 }
+
+/// Bucket Schema Type
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemaType {
+    /// Schema is defined by the data written
+    #[default]
+    Implicit,
+    /// Measurements must match an explicit schema
+    Explicit,
+}
+
+/// Request to create a bucket through api/v2/buckets
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CreateBucketRequest {
+    /// Org it is owned by
+    #[serde(rename = "orgID")]
+    pub org_id: String,
+    /// Name of bucket
+    pub name: String,
+    /// Optional Description of Bucket
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Retention Policy name for the v1 compatible endpoints
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rp: Option<String>,
+    /// Retention Rules, none means data never expires
+    #[serde(rename = "retentionRules")]
+    pub retention_rules: Vec<RetentionRules>,
+    /// Schema Type
+    #[serde(rename = "schemaType", skip_serializing_if = "Option::is_none")]
+    pub schema_type: Option<SchemaType>,
+    /// IDs of labels added once the bucket is created
+    #[serde(skip)]
+    pub labels: Vec<String>,
+}
+
+impl CreateBucketRequest {
+    /// Bucket in the given org that keeps data forever
+    pub fn new<T: Into<String>>(org_id: T, name: T) -> Self {
+        CreateBucketRequest {
+            org_id: org_id.into(),
+            name: name.into(),
+            description: None,
+            rp: None,
+            retention_rules: Vec::new(),
+            schema_type: None,
+            labels: Vec::new(),
+        }
+    }
+
+    /// Set the description
+    pub fn description<T: Into<String>>(mut self, description: T) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Expire data after `retention`
+    pub fn retention(mut self, retention: Duration) -> Self {
+        self.retention_rules = vec![RetentionRules::expire(retention)];
+        self
+    }
+
+    /// Expire data after `retention`, using shard groups of `shard_group_duration`
+    pub fn retention_with_shard_group(mut self, retention: Duration, shard_group_duration: Duration) -> Self {
+        self.retention_rules = vec![RetentionRules::expire(retention).shard_group_duration(shard_group_duration)];
+        self
+    }
+
+    /// Set the retention policy name
+    pub fn rp<T: Into<String>>(mut self, rp: T) -> Self {
+        self.rp = Some(rp.into());
+        self
+    }
+
+    /// Set the schema type
+    pub fn schema_type(mut self, schema_type: SchemaType) -> Self {
+        self.schema_type = Some(schema_type);
+        self
+    }
+
+    /// Add a label by ID
+    pub fn label<T: Into<String>>(mut self, label_id: T) -> Self {
+        self.labels.push(label_id.into());
+        self
+    }
+}
+
+/// Request to update a bucket through api/v2/buckets/{id}
+/// Only the fields that are set are changed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct UpdateBucketRequest {
+    /// New name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// New description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// New retention rules
+    #[serde(rename = "retentionRules", skip_serializing_if = "Option::is_none")]
+    pub retention_rules: Option<Vec<RetentionRules>>,
+}
+
+impl UpdateBucketRequest {
+    /// Rename the bucket
+    pub fn name<T: Into<String>>(mut self, name: T) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Change the description
+    pub fn description<T: Into<String>>(mut self, description: T) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Change the retention, Duration::ZERO keeps data forever
+    pub fn retention(mut self, retention: Duration) -> Self {
+        self.retention_rules = Some(vec![RetentionRules::expire(retention)]);
+        self
+    }
+}
+
+/// Filter for listing buckets through api/v2/buckets
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct BucketFilter {
+    /// Org name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub org: Option<String>,
+    /// Org ID
+    #[serde(rename = "orgID", skip_serializing_if = "Option::is_none")]
+    pub org_id: Option<String>,
+    /// Bucket name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Bucket ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+impl BucketFilter {
+    /// Only buckets of the org with this name
+    pub fn org<T: Into<String>>(mut self, org: T) -> Self {
+        self.org = Some(org.into());
+        self
+    }
+
+    /// Only buckets of the org with this ID
+    pub fn org_id<T: Into<String>>(mut self, org_id: T) -> Self {
+        self.org_id = Some(org_id.into());
+        self
+    }
+
+    /// Only the bucket with this name
+    pub fn name<T: Into<String>>(mut self, name: T) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Only the bucket with this ID
+    pub fn id<T: Into<String>>(mut self, id: T) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub(crate) fn params(&self) -> Vec<(&str, &str)> {
+        let mut params = Vec::new();
+        if let Some(ref org) = self.org {
+            params.push(("org", org.as_str()));
+        }
+        if let Some(ref org_id) = self.org_id {
+            params.push(("orgID", org_id.as_str()));
+        }
+        if let Some(ref name) = self.name {
+            params.push(("name", name.as_str()));
+        }
+        if let Some(ref id) = self.id {
+            params.push(("id", id.as_str()));
+        }
+        params
+    }
+}
+
+/// Request to add a label to a resource
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AddLabel {
+    /// Label ID
+    #[serde(rename = "labelID")]
+    pub label_id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serialize_create_bucket() {
+        let request = CreateBucketRequest::new("0123", "metrics")
+            .description("edge metrics")
+            .retention_with_shard_group(Duration::from_secs(7 * 86400), Duration::from_secs(86400))
+            .schema_type(SchemaType::Explicit)
+            .label("label1");
+
+        assert_eq!(
+            json!(request),
+            json!({
+                "orgID": "0123",
+                "name": "metrics",
+                "description": "edge metrics",
+                "retentionRules": [{"everySeconds": 604800, "shardGroupDurationSeconds": 86400, "type": "expire"}],
+                "schemaType": "explicit",
+            })
+        );
+    }
+
+    #[test]
+    fn serialize_update_bucket() {
+        let request = UpdateBucketRequest::default().retention(Duration::ZERO);
+
+        assert_eq!(
+            json!(request),
+            json!({"retentionRules": [{"everySeconds": 0, "type": "expire"}]})
+        );
+    }
+}
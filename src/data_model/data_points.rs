@@ -17,6 +17,8 @@ pub enum Value<'a> {
     String(Cow<'a, str>),
     /// Integer
     Integer(i64),
+    /// Unsigned integer
+    UInteger(u64),
     /// float
     Float(f64),
     /// Bool
@@ -149,6 +151,18 @@ impl Precision {
             Precision::Hours => "h",
         }
     }
+
+    /// Convert Precision to the code used by the v1 endpoints
+    pub fn to_v1_str(&self) -> &str {
+        match *self {
+            Precision::Nanoseconds => "ns",
+            Precision::Microseconds => "u",
+            Precision::Milliseconds => "ms",
+            Precision::Seconds => "s",
+            Precision::Minutes => "m",
+            Precision::Hours => "h",
+        }
+    }
 }
 
 /// Create Points by macro
@@ -223,6 +237,12 @@ impl<'a> From<i8> for Value<'a> {
     }
 }
 
+impl<'a> From<u64> for Value<'a> {
+    fn from(v: u64) -> Self {
+        Self::UInteger(v)
+    }
+}
+
 impl<'a> From<f64> for Value<'a> {
     fn from(v: f64) -> Self {
         Self::Float(v)
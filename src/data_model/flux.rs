@@ -0,0 +1,131 @@
+use chrono::{DateTime, Duration, FixedOffset};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+
+use crate::api::flux_de;
+use crate::error;
+
+/// Typed value of a single Flux result cell
+#[derive(Debug, Clone, PartialEq)]
+pub enum FluxValue {
+    /// Empty cell without a default value
+    Null,
+    /// long
+    Long(i64),
+    /// unsignedLong
+    UnsignedLong(u64),
+    /// double
+    Double(f64),
+    /// boolean
+    Boolean(bool),
+    /// string, or any datatype the parser does not know
+    String(String),
+    /// dateTime:RFC3339 / dateTime:RFC3339Nano
+    DateTime(DateTime<FixedOffset>),
+    /// duration
+    Duration(Duration),
+}
+
+impl FluxValue {
+    /// Returns the string if the value is a string
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            FluxValue::String(s) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Returns true for an empty cell
+    pub fn is_null(&self) -> bool {
+        matches!(self, FluxValue::Null)
+    }
+}
+
+/// Column of a Flux table, built from the annotation and header rows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FluxColumn {
+    /// Position of the column, not counting the annotation column
+    pub index: usize,
+    /// Column label
+    pub name: String,
+    /// Value of the #datatype annotation
+    pub data_type: String,
+    /// Value of the #group annotation
+    pub group: bool,
+    /// Value of the #default annotation
+    pub default_value: String,
+}
+
+/// Single row of a Flux table
+#[derive(Debug, Clone, PartialEq)]
+pub struct FluxRecord {
+    /// Position of the table this record belongs to within the response
+    pub table: usize,
+    /// Values by column name
+    pub values: HashMap<String, FluxValue>,
+}
+
+impl FluxRecord {
+    /// Value of the given column
+    pub fn get(&self, column: &str) -> Option<&FluxValue> {
+        self.values.get(column)
+    }
+
+    /// _time
+    pub fn time(&self) -> Option<&DateTime<FixedOffset>> {
+        self.datetime("_time")
+    }
+
+    /// _start
+    pub fn start(&self) -> Option<&DateTime<FixedOffset>> {
+        self.datetime("_start")
+    }
+
+    /// _stop
+    pub fn stop(&self) -> Option<&DateTime<FixedOffset>> {
+        self.datetime("_stop")
+    }
+
+    /// _value
+    pub fn value(&self) -> Option<&FluxValue> {
+        self.get("_value")
+    }
+
+    /// _field
+    pub fn field(&self) -> Option<&str> {
+        self.get("_field").and_then(FluxValue::as_str)
+    }
+
+    /// _measurement
+    pub fn measurement(&self) -> Option<&str> {
+        self.get("_measurement").and_then(FluxValue::as_str)
+    }
+
+    /// Deserialize the record into `T`, mapping columns to fields by name
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, error::Error> {
+        flux_de::from_record(self)
+    }
+
+    fn datetime(&self, column: &str) -> Option<&DateTime<FixedOffset>> {
+        match self.get(column) {
+            Some(FluxValue::DateTime(t)) => Some(t),
+            _ => None,
+        }
+    }
+}
+
+/// Table of a Flux query result
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FluxTable {
+    /// Columns of the table
+    pub columns: Vec<FluxColumn>,
+    /// Rows of the table
+    pub records: Vec<FluxRecord>,
+}
+
+impl FluxTable {
+    /// Columns that are part of the group key
+    pub fn group_key(&self) -> Vec<&FluxColumn> {
+        self.columns.iter().filter(|c| c.group).collect()
+    }
+}
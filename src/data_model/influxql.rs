@@ -0,0 +1,142 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+
+/// Response of the v1 compatible /query endpoint
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct InfluxQlResponse {
+    /// One result per statement of the query
+    #[serde(default)]
+    pub results: Vec<StatementResult>,
+    /// Error affecting the whole query
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of a single InfluxQL statement
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct StatementResult {
+    /// Position of the statement within the query
+    #[serde(default)]
+    pub statement_id: u32,
+    /// Series returned by the statement
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub series: Vec<Series>,
+    /// Informational messages
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<Message>,
+    /// More chunks follow for this statement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partial: Option<bool>,
+    /// Error of this statement
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Rows of one measurement and tag set
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Series {
+    /// Measurement name
+    #[serde(default)]
+    pub name: String,
+    /// Tags the series is grouped by
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, String>,
+    /// Column names
+    #[serde(default)]
+    pub columns: Vec<String>,
+    /// Rows, one value per column
+    #[serde(default)]
+    pub values: Vec<Vec<serde_json::Value>>,
+    /// More chunks follow for this series
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partial: Option<bool>,
+}
+
+impl Series {
+    /// Value of the given column in the given row
+    pub fn get(&self, row: usize, column: &str) -> Option<&serde_json::Value> {
+        let index = self.columns.iter().position(|c| c == column)?;
+        self.values.get(row)?.get(index)
+    }
+}
+
+/// Informational message returned with a statement
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Message {
+    /// Level of the message
+    pub level: String,
+    /// Text of the message
+    pub text: String,
+}
+
+impl InfluxQlResponse {
+    /// Merge the next chunk of a chunked response into this one
+    pub(crate) fn merge(&mut self, chunk: InfluxQlResponse) {
+        if chunk.error.is_some() {
+            self.error = chunk.error;
+        }
+
+        for result in chunk.results {
+            let existing = self
+                .results
+                .iter_mut()
+                .find(|r| r.statement_id == result.statement_id);
+
+            match existing {
+                Some(existing) => existing.merge(result),
+                None => self.results.push(result),
+            }
+        }
+    }
+}
+
+impl StatementResult {
+    fn merge(&mut self, chunk: StatementResult) {
+        for series in chunk.series {
+            match self.series.last_mut() {
+                Some(last)
+                    if last.partial == Some(true)
+                        && last.name == series.name
+                        && last.tags == series.tags =>
+                {
+                    last.values.extend(series.values);
+                    last.partial = series.partial;
+                }
+                _ => self.series.push(series),
+            }
+        }
+
+        self.messages.extend(chunk.messages);
+        self.partial = chunk.partial;
+        if chunk.error.is_some() {
+            self.error = chunk.error;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_chunks() {
+        let body = r#"{"results":[{"statement_id":0,"series":[{"name":"cpu","tags":{"host":"a"},"columns":["time","value"],"values":[[1,0.5],[2,0.6]],"partial":true}],"partial":true}]}
+{"results":[{"statement_id":0,"series":[{"name":"cpu","tags":{"host":"a"},"columns":["time","value"],"values":[[3,0.7]]},{"name":"cpu","tags":{"host":"b"},"columns":["time","value"],"values":[[1,1.5]]}]}]}
+"#;
+
+        let mut response = InfluxQlResponse::default();
+        for chunk in serde_json::Deserializer::from_str(body).into_iter::<InfluxQlResponse>() {
+            response.merge(chunk.unwrap());
+        }
+
+        assert_eq!(response.results.len(), 1);
+
+        let series = &response.results[0].series;
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].values.len(), 3);
+        assert_eq!(series[0].partial, None);
+        assert_eq!(series[0].get(2, "value"), Some(&serde_json::json!(0.7)));
+        assert_eq!(series[1].tags["host"], "b");
+        assert_eq!(response.results[0].partial, None);
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Serialize, Deserialize};
+use crate::data_model::links::Links;
 
 /// Label Struct
 #[derive(Serialize, Deserialize, Debug)]
@@ -20,7 +21,19 @@ pub struct Labels {
 pub struct Properties {
 
     /// Color of Label
+    #[serde(default)]
     pub color: String,
     /// Description,
+    #[serde(default)]
     pub description: String,
-}
\ No newline at end of file
+}
+
+/// List of Labels from api/v2/labels
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LabelsResponse {
+    /// Links
+    #[serde(default)]
+    pub links: Links,
+    /// Labels
+    pub labels: Vec<Labels>,
+}
@@ -20,7 +20,7 @@ pub struct BucketLinks{
 }
 
 /// Page Links
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Links {
     /// Next Links Page
     pub next: Option<String>,
@@ -30,3 +30,50 @@ pub struct Links {
     #[serde(rename = "self")]
     pub link_self: Option<String>,
 }
+
+/// Paging parameters for the first page of a listing
+///
+/// Later pages are requested through `links.next`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PageOptions {
+    /// Number of items per page
+    pub limit: Option<u32>,
+    /// Number of items to skip
+    pub offset: Option<u32>,
+    /// Only items after this ID, for cursor paged listings like tasks
+    pub after: Option<String>,
+}
+
+impl PageOptions {
+    /// Number of items per page
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Number of items to skip
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Only items after this ID
+    pub fn after<T: Into<String>>(mut self, after: T) -> Self {
+        self.after = Some(after.into());
+        self
+    }
+
+    pub(crate) fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(limit) = self.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        if let Some(offset) = self.offset {
+            params.push(("offset", offset.to_string()));
+        }
+        if let Some(ref after) = self.after {
+            params.push(("after", after.clone()));
+        }
+        params
+    }
+}
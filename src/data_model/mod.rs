@@ -17,4 +17,10 @@ pub mod file;
 /// User Structs and Enums
 pub mod user;
 /// Authorization Structs
-pub mod authorization;
\ No newline at end of file
+pub mod authorization;
+/// Task Structs
+pub mod task;
+/// Flux Query Result Tables and Records
+pub mod flux;
+/// InfluxQL Response Structs
+pub mod influxql;
\ No newline at end of file
@@ -1,5 +1,5 @@
 use serde::{Serialize, Deserialize};
-use crate::data_model::{links::Links};
+use crate::data_model::{links::Links, user::UserLinks};
 
 /// Org Struct when retrieving Orgs api/v2/orgs
 #[derive(Serialize, Deserialize, Debug)]
@@ -54,3 +54,121 @@ pub struct Orgs {
     pub orgs: Vec<OrgStruct>,
 }
 
+
+/// Request to create an Org through api/v2/orgs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CreateOrgRequest {
+    /// Name of Org
+    pub name: String,
+    /// Description of Org
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl CreateOrgRequest {
+    /// Org with the given name
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        CreateOrgRequest {
+            name: name.into(),
+            description: None,
+        }
+    }
+
+    /// Description of the Org
+    pub fn description<T: Into<String>>(mut self, description: T) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
+/// Request to change an Org, unset fields are left untouched
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct UpdateOrgRequest {
+    /// New name of Org
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// New description of Org
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl UpdateOrgRequest {
+    /// Rename the Org
+    pub fn name<T: Into<String>>(mut self, name: T) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Change the description of the Org
+    pub fn description<T: Into<String>>(mut self, description: T) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
+/// Role of a user within a resource
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Member
+    Member,
+    /// Owner
+    Owner,
+}
+
+/// Member or Owner of an Org
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ResourceMember {
+    /// User ID
+    pub id: String,
+    /// User Name
+    pub name: String,
+    /// User Status
+    pub status: Option<String>,
+    /// Role within the Org
+    pub role: Role,
+    /// Links
+    pub links: Option<UserLinks>,
+}
+
+/// Members or Owners of an Org from api/v2/orgs/{id}/members and /owners
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ResourceMembers {
+    /// Links
+    #[serde(default)]
+    pub links: Links,
+    /// Users
+    pub users: Vec<ResourceMember>,
+}
+
+/// Request to add a user as Member or Owner
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AddResourceMember {
+    /// User ID
+    pub id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serialize_org_requests() {
+        let create = CreateOrgRequest::new("edge").description("edge devices");
+        assert_eq!(serde_json::to_value(&create).unwrap(), json!({"name": "edge", "description": "edge devices"}));
+
+        let update = UpdateOrgRequest::default().name("edge-eu");
+        assert_eq!(serde_json::to_value(&update).unwrap(), json!({"name": "edge-eu"}));
+    }
+
+    #[test]
+    fn deserialize_owners() {
+        let body = r#"{"links":{"self":"/api/v2/orgs/01/owners"},
+            "users":[{"id":"0a","name":"admin","status":"active","role":"owner","links":{"self":"/api/v2/users/0a"}}]}"#;
+
+        let owners: ResourceMembers = serde_json::from_str(body).unwrap();
+        assert_eq!(owners.users.len(), 1);
+        assert_eq!(owners.users[0].role, Role::Owner);
+    }
+}
@@ -1,8 +1,11 @@
 // inspo from influxdb2_client
 // utilized some structs and serde elements
 
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Serializer, Deserialize};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use std::collections::HashMap;
 use crate::data_model::file::File;
+use crate::flux_query::duration_literal;
 
 /// Struct designed for Deleting Data
 #[derive(Serialize, Deserialize, Debug)]
@@ -29,13 +32,198 @@ pub struct ReadQuery {
     /// The type of query. Must be \"flux\".
     #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     pub r#type: Option<Type>,
-    /// Dialect
+    /// Options for the CSV response
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub dialect: Option<String>,
+    pub dialect: Option<Dialect>,
     /// Specifies the time that should be reported as "now" in the query.
     /// Default is the server's now time.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub now: Option<String>,
+    /// Values available to the query as `params.<name>`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<HashMap<String, QueryParam>>,
+}
+
+impl ReadQuery {
+    /// Create a Flux query
+    pub fn new<T: Into<String>>(query: T) -> Self {
+        ReadQuery {
+            query: query.into(),
+            r#type: Some(Type::Flux),
+            ..Default::default()
+        }
+    }
+
+    /// Bind a value the query can reference as `params.<name>`
+    ///
+    /// Query parameters are supported by InfluxDB Cloud only.
+    pub fn bind<T: Into<String>, V: Into<QueryParam>>(mut self, name: T, value: V) -> Self {
+        self.params
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), value.into());
+        self
+    }
+}
+
+/// Options for the annotated CSV returned by api/v2/query
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Dialect {
+    /// Include the header row
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<bool>,
+    /// Separator between cells, a single character
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delimiter: Option<String>,
+    /// Annotation rows to include
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
+    /// Prefix of the annotation rows
+    #[serde(rename = "commentPrefix", skip_serializing_if = "Option::is_none")]
+    pub comment_prefix: Option<String>,
+    /// Format of the dateTime columns
+    #[serde(rename = "dateTimeFormat", skip_serializing_if = "Option::is_none")]
+    pub date_time_format: Option<DateTimeFormat>,
+}
+
+/// Dialect with header, `,` delimiter and every annotation, as the CSV parser expects
+impl Default for Dialect {
+    fn default() -> Self {
+        Dialect {
+            header: Some(true),
+            delimiter: Some(",".to_string()),
+            annotations: vec![Annotation::Datatype, Annotation::Group, Annotation::Default],
+            comment_prefix: Some("#".to_string()),
+            date_time_format: Some(DateTimeFormat::RFC3339),
+        }
+    }
+}
+
+impl Dialect {
+    /// Set whether to include the header row
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// Set the annotations to include
+    pub fn annotations(mut self, annotations: Vec<Annotation>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Set the format of the dateTime columns
+    pub fn date_time_format(mut self, format: DateTimeFormat) -> Self {
+        self.date_time_format = Some(format);
+        self
+    }
+}
+
+/// Annotation row of the CSV response
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Annotation {
+    /// #group
+    Group,
+    /// #datatype
+    Datatype,
+    /// #default
+    Default,
+}
+
+/// Format of the dateTime columns
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum DateTimeFormat {
+    /// RFC3339
+    RFC3339,
+    /// RFC3339 with nanoseconds
+    RFC3339Nano,
+}
+
+/// Value of a query parameter
+///
+/// Times and durations are sent as strings, so convert them in Flux with
+/// `time(v: params.start)` and `duration(v: params.every)`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum QueryParam {
+    /// String
+    String(String),
+    /// Integer
+    Integer(i64),
+    /// Float
+    Float(f64),
+    /// Boolean
+    Boolean(bool),
+    /// Time, sent as RFC3339
+    #[serde(skip)]
+    Time(DateTime<Utc>),
+    /// Duration, sent as a Flux duration literal
+    #[serde(skip)]
+    Duration(Duration),
+}
+
+impl Serialize for QueryParam {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            QueryParam::String(s) => serializer.serialize_str(s),
+            QueryParam::Integer(i) => serializer.serialize_i64(*i),
+            QueryParam::Float(f) => serializer.serialize_f64(*f),
+            QueryParam::Boolean(b) => serializer.serialize_bool(*b),
+            QueryParam::Time(t) => {
+                serializer.serialize_str(&t.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
+            QueryParam::Duration(d) => serializer.serialize_str(&duration_literal(d)),
+        }
+    }
+}
+
+impl From<String> for QueryParam {
+    fn from(v: String) -> Self {
+        QueryParam::String(v)
+    }
+}
+
+impl From<&str> for QueryParam {
+    fn from(v: &str) -> Self {
+        QueryParam::String(v.to_string())
+    }
+}
+
+impl From<i64> for QueryParam {
+    fn from(v: i64) -> Self {
+        QueryParam::Integer(v)
+    }
+}
+
+impl From<i32> for QueryParam {
+    fn from(v: i32) -> Self {
+        QueryParam::Integer(v.into())
+    }
+}
+
+impl From<f64> for QueryParam {
+    fn from(v: f64) -> Self {
+        QueryParam::Float(v)
+    }
+}
+
+impl From<bool> for QueryParam {
+    fn from(v: bool) -> Self {
+        QueryParam::Boolean(v)
+    }
+}
+
+impl From<DateTime<Utc>> for QueryParam {
+    fn from(v: DateTime<Utc>) -> Self {
+        QueryParam::Time(v)
+    }
+}
+
+impl From<Duration> for QueryParam {
+    fn from(v: Duration) -> Self {
+        QueryParam::Duration(v)
+    }
 }
 
 /// The type of query. Must be \"flux\".
@@ -84,4 +272,94 @@ impl AnalyzeQueryResponseErrors {
     pub fn new() -> Self {
         Self::default()
     }
-}
\ No newline at end of file
+}
+
+/// Flux query to parse into an AST, api/v2/query/ast
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct LanguageRequest {
+    /// Flux query script to be analyzed
+    pub query: String,
+}
+
+/// Abstract syntax tree of a Flux query
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct AstResponse {
+    /// Flux package AST, left untyped as its node types are numerous
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ast: Option<serde_json::Value>,
+}
+
+/// Flux functions available for suggestions, api/v2/query/suggestions
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct FluxSuggestions {
+    /// Functions
+    #[serde(default)]
+    pub funcs: Vec<FluxSuggestion>,
+}
+
+/// Flux function and its parameters
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct FluxSuggestion {
+    /// Function name
+    pub name: String,
+    /// Parameter names and their types
+    #[serde(default)]
+    pub params: HashMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn serialize_params() {
+        let query = ReadQuery::new("from(bucket: params.bucket) |> range(start: time(v: params.start))")
+            .bind("bucket", "test_bucket")
+            .bind("limit", 10)
+            .bind("threshold", 0.5)
+            .bind("desc", true)
+            .bind("start", Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap())
+            .bind("every", Duration::minutes(5));
+
+        assert_eq!(
+            json!(query)["params"],
+            json!({
+                "bucket": "test_bucket",
+                "limit": 10,
+                "threshold": 0.5,
+                "desc": true,
+                "start": "2022-01-01T00:00:00Z",
+                "every": "5m",
+            })
+        );
+        assert_eq!(json!(query)["type"], json!("flux"));
+    }
+
+    #[test]
+    fn serialize_dialect() {
+        let query = ReadQuery {
+            dialect: Some(Dialect::default().date_time_format(DateTimeFormat::RFC3339Nano)),
+            ..ReadQuery::new("buckets()")
+        };
+
+        assert_eq!(
+            json!(query)["dialect"],
+            json!({
+                "header": true,
+                "delimiter": ",",
+                "annotations": ["datatype", "group", "default"],
+                "commentPrefix": "#",
+                "dateTimeFormat": "RFC3339Nano",
+            })
+        );
+    }
+
+    #[test]
+    fn params_skipped_when_unbound() {
+        let query = ReadQuery::new("buckets()");
+
+        assert!(json!(query).get("params").is_none());
+    }
+}
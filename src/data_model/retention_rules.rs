@@ -1,16 +1,44 @@
 use serde::{Serialize, Deserialize};
+use std::time::Duration;
 
 /// Rules to expire or retain data. No rules means data never expires.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct RetentionRules{
     ///Duration in seconds for how long data will be kept in the database. 0 means infinite.
     #[serde(rename = "everySeconds")]
     pub every_seconds: i64,
     ///Shard duration measured in seconds.
-    #[serde(rename = "shardGroupDurationSeconds")]
-    pub shard_group_duration_seconds: i64,
+    ///None lets InfluxDB pick one based on the retention period.
+    #[serde(rename = "shardGroupDurationSeconds", default, skip_serializing_if = "Option::is_none")]
+    pub shard_group_duration_seconds: Option<i64>,
     ///Default: "expire"
     /// Value: "expire"
-    #[serde(rename = "type")]
-    pub retention_type: String,
-}
\ No newline at end of file
+    #[serde(rename = "type", default)]
+    pub retention_type: RetentionType,
+}
+
+impl RetentionRules {
+    /// Expire data older than `every`, Duration::ZERO keeps data forever
+    pub fn expire(every: Duration) -> Self {
+        RetentionRules {
+            every_seconds: every.as_secs() as i64,
+            shard_group_duration_seconds: None,
+            retention_type: RetentionType::Expire,
+        }
+    }
+
+    /// Set the shard group duration
+    pub fn shard_group_duration(mut self, duration: Duration) -> Self {
+        self.shard_group_duration_seconds = Some(duration.as_secs() as i64);
+        self
+    }
+}
+
+/// Retention Rule Type
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionType {
+    /// Expire
+    #[default]
+    Expire,
+}
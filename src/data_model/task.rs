@@ -0,0 +1,59 @@
+use serde::{Serialize, Deserialize};
+use crate::data_model::{labels::Labels, links::Links};
+
+/// Task Struct when retrieving Tasks from api/v2/tasks
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Task {
+    /// Task ID
+    pub id: String,
+    /// Org ID
+    #[serde(rename = "orgID")]
+    pub org_id: String,
+    /// Org name
+    pub org: Option<String>,
+    /// Name of Task
+    pub name: String,
+    /// Owner ID
+    #[serde(rename = "ownerID")]
+    pub owner_id: Option<String>,
+    /// Description of Task
+    pub description: Option<String>,
+    /// Status "active" / "inactive"
+    pub status: Option<String>,
+    /// Array of Labels
+    #[serde(default)]
+    pub labels: Vec<Labels>,
+    /// Flux script of the Task
+    pub flux: String,
+    /// Run interval
+    pub every: Option<String>,
+    /// Cron schedule
+    pub cron: Option<String>,
+    /// Delay after the scheduled time
+    pub offset: Option<String>,
+    /// Time of the latest completed run
+    #[serde(rename = "latestCompleted")]
+    pub latest_completed: Option<String>,
+    /// Status of the last run
+    #[serde(rename = "lastRunStatus")]
+    pub last_run_status: Option<String>,
+    /// Error of the last run
+    #[serde(rename = "lastRunError")]
+    pub last_run_error: Option<String>,
+    /// Created At
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<String>,
+    /// Last Updated
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<String>,
+}
+
+/// Tasks Struct when retrieving list of Tasks from api/v2/tasks
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Tasks {
+    /// Links
+    #[serde(default)]
+    pub links: Links,
+    /// Task Structs
+    pub tasks: Vec<Task>,
+}
@@ -1,10 +1,12 @@
 /// User data model
 use serde::{Serialize, Deserialize};
+use crate::data_model::links::Links;
 
 
 
 /// User Status Enum
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
 pub enum Status {
     /// Active
     Active,
@@ -18,7 +20,7 @@ pub struct CreateUser {
     /// Name of the User
     pub name: String,
     /// Status of User
-    pub status: String,
+    pub status: Status,
 }
 
 /// Create User Response
@@ -29,7 +31,8 @@ pub struct UserResponse {
     /// User Name
     pub name: String,
     /// User Status
-    pub status: String,
+    #[serde(default = "default_status")]
+    pub status: Status,
     /// Links
     pub links: UserLinks,
 }
@@ -49,5 +52,101 @@ pub struct ListUserResponse {
     /// Users
     pub users: Vec<UserResponse>,
     /// Links
-    pub links: UserLinks,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub links: Links,
+}
+
+fn default_status() -> Status {
+    Status::Active
+}
+
+/// Request to change a user, unset fields are left untouched
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct UpdateUserRequest {
+    /// New name of the User
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// New status of the User
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+}
+
+impl UpdateUserRequest {
+    /// Rename the User
+    pub fn name<T: Into<String>>(mut self, name: T) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Activate or deactivate the User
+    pub fn status(mut self, status: Status) -> Self {
+        self.status = Some(status);
+        self
+    }
+}
+
+/// Filter for listing users through api/v2/users
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct UserFilter {
+    /// User name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// User ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+impl UserFilter {
+    /// Only the user with this name
+    pub fn name<T: Into<String>>(mut self, name: T) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Only the user with this ID
+    pub fn id<T: Into<String>>(mut self, id: T) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub(crate) fn params(&self) -> Vec<(&str, &str)> {
+        let mut params = Vec::new();
+        if let Some(ref name) = self.name {
+            params.push(("name", name.as_str()));
+        }
+        if let Some(ref id) = self.id {
+            params.push(("id", id.as_str()));
+        }
+        params
+    }
+}
+
+/// New password of a User
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PasswordRequest {
+    /// Password
+    pub password: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn status_lowercase() {
+        let update = UpdateUserRequest::default().status(Status::Inactive);
+        assert_eq!(serde_json::to_value(&update).unwrap(), json!({"status": "inactive"}));
+
+        let user: UserResponse = serde_json::from_value(json!({
+            "id": "0a", "name": "admin", "status": "active", "links": {"self": "/api/v2/users/0a"}
+        })).unwrap();
+        assert_eq!(user.status, Status::Active);
+    }
+
+    #[test]
+    fn filter_params() {
+        let filter = UserFilter::default().name("admin");
+        assert_eq!(filter.params(), vec![("name", "admin")]);
+    }
+}
@@ -4,6 +4,10 @@ use std::io;
 use futures::Future;
 use std::pin::Pin;
 use futures::task::{Poll, Context};
+use std::time::Duration;
+
+use crate::retry;
+use crate::write_error::WriteError;
 
 
 /// Influxdb-rs Error
@@ -23,33 +27,233 @@ impl fmt::Display for Error {
 /// The error of influxdb client
 #[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
 pub enum ErrorKind {
-    /// Syntax error
+    /// Invalid input, or a response the client could not make sense of
     SyntaxError(String),
-    /// Invalid credentials
+    /// Credentials the client could not use, e.g. a session without a cookie
     InvalidCredentials(String),
     /// The specified database does not exist
     DataBaseDoesNotExist(String),
-    /// The specified retention policy does not exist
-    RetentionPolicyDoesNotExist(String),
-    /// Some error on build url or io.
+    /// Failure talking to a background task
     Communication(String),
+    /// 400 Bad Request
+    BadRequest(Box<ApiError>),
+    /// 401 Unauthorized
+    Unauthorized(Box<ApiError>),
+    /// 403 Forbidden
+    Forbidden(Box<ApiError>),
+    /// 404 Not Found
+    NotFound(Box<ApiError>),
+    /// 413 Payload Too Large
+    PayloadTooLarge(Box<ApiError>),
+    /// 422 Unprocessable Entity
+    Unprocessable(Box<ApiError>),
+    /// 429 Too Many Requests
+    RateLimited {
+        /// Error returned by InfluxDB
+        error: Box<ApiError>,
+        /// Delay asked for through the `Retry-After` header
+        retry_after: Option<Duration>,
+    },
+    /// 5xx
+    ServerError(Box<ApiError>),
+    /// Any other unexpected status
+    Api(Box<ApiError>),
+    /// 400 or 422 for a write, naming the lines that were rejected
+    PartialWrite(Box<WriteError>),
+    /// The request could not be sent or the response not received
+    Transport {
+        /// Description of the failure
+        message: String,
+        /// Connection failures and timeouts are worth retrying
+        retryable: bool,
+    },
+    /// A write body could not be compressed
+    Compression(String),
     /// Some other error, I don't expect
     Unknown(String),
 }
 
+/// Error response of the InfluxDB HTTP API
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
+pub struct ApiError {
+    /// HTTP status
+    pub status: u16,
+    /// InfluxDB error code, e.g. `not found` or `invalid`
+    pub code: Option<String>,
+    /// Human readable message
+    pub message: String,
+    /// Operation that failed
+    pub op: Option<String>,
+    /// Underlying error
+    pub err: Option<String>,
+    /// Request method
+    pub method: String,
+    /// Request URL path
+    pub path: String,
+}
+
+/// Error body as sent by InfluxDB 2.x (`code`/`message`) and 1.x (`error`)
+#[derive(Deserialize)]
+struct ErrorBody {
+    code: Option<String>,
+    message: Option<String>,
+    op: Option<String>,
+    err: Option<String>,
+    error: Option<String>,
+}
+
+/// Method of the request a response belongs to, stored in the response extensions by `Client::send`
+#[derive(Clone)]
+pub(crate) struct RequestMethod(pub(crate) reqwest::Method);
+
+impl ApiError {
+    /// Parse the error body of a response
+    pub(crate) fn parse(status: u16, method: &str, path: &str, body: &str) -> Self {
+        let parsed = serde_json::from_str::<ErrorBody>(body).ok();
+
+        let (code, message, op, err) = match parsed {
+            Some(body) => {
+                let message = body.message.or(body.error).unwrap_or_else(|| body.err.clone().unwrap_or_default());
+                (body.code, message, body.op, body.err)
+            }
+            None => (None, body.trim().to_string(), None, None),
+        };
+
+        ApiError {
+            status,
+            code,
+            message,
+            op,
+            err,
+            method: method.to_string(),
+            path: path.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}: {}", self.method, self.path, self.status)?;
+        if let Some(ref code) = self.code {
+            write!(f, " {}", code)?;
+        }
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
+        }
+        if let Some(ref err) = self.err {
+            if *err != self.message {
+                write!(f, " ({})", err)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             ErrorKind::SyntaxError(ref t) => write!(f, "{}", t),
             ErrorKind::InvalidCredentials(ref t) => write!(f, "{}", t),
             ErrorKind::DataBaseDoesNotExist(ref t) => write!(f, "{}", t),
-            ErrorKind::RetentionPolicyDoesNotExist(ref t) => write!(f, "{}", t),
             ErrorKind::Communication(ref t) => write!(f, "{}", t),
+            ErrorKind::BadRequest(ref e) => write!(f, "{}", e),
+            ErrorKind::Unauthorized(ref e) => write!(f, "{}", e),
+            ErrorKind::Forbidden(ref e) => write!(f, "{}", e),
+            ErrorKind::NotFound(ref e) => write!(f, "{}", e),
+            ErrorKind::PayloadTooLarge(ref e) => write!(f, "{}", e),
+            ErrorKind::Unprocessable(ref e) => write!(f, "{}", e),
+            ErrorKind::RateLimited { ref error, .. } => write!(f, "{}", error),
+            ErrorKind::ServerError(ref e) => write!(f, "{}", e),
+            ErrorKind::Api(ref e) => write!(f, "{}", e),
+            ErrorKind::PartialWrite(ref e) => write!(f, "{}", e),
+            ErrorKind::Transport { ref message, .. } => write!(f, "{}", message),
+            ErrorKind::Compression(ref t) => write!(f, "Failed to compress write body: {}", t),
             ErrorKind::Unknown(ref t) => write!(f, "{}", t),
         }
     }
 }
 
+impl Error {
+    /// Error for an unsuccessful response, parsing the InfluxDB error body
+    pub(crate) async fn from_response(res: reqwest::Response) -> Self {
+        let status = res.status().as_u16();
+        let path = res.url().path().to_string();
+        let method = res
+            .extensions()
+            .get::<RequestMethod>()
+            .map(|m| m.0.to_string())
+            .unwrap_or_default();
+        let retry_after = retry::retry_after(&res);
+
+        let body = match res.text().await {
+            Ok(body) => body,
+            Err(err) => return err.into(),
+        };
+
+        Error::from_status(ApiError::parse(status, &method, &path, &body), retry_after)
+    }
+
+    pub(crate) fn from_status(error: ApiError, retry_after: Option<Duration>) -> Self {
+        let error = Box::new(error);
+        let inner = match error.status {
+            400 => ErrorKind::BadRequest(error),
+            401 => ErrorKind::Unauthorized(error),
+            403 => ErrorKind::Forbidden(error),
+            404 => ErrorKind::NotFound(error),
+            413 => ErrorKind::PayloadTooLarge(error),
+            422 => ErrorKind::Unprocessable(error),
+            429 => ErrorKind::RateLimited { error, retry_after },
+            500..=599 => ErrorKind::ServerError(error),
+            _ => ErrorKind::Api(error),
+        };
+
+        Error { inner }
+    }
+
+    /// Error returned by InfluxDB, if the request got a response
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self.inner {
+            ErrorKind::BadRequest(ref e)
+            | ErrorKind::Unauthorized(ref e)
+            | ErrorKind::Forbidden(ref e)
+            | ErrorKind::NotFound(ref e)
+            | ErrorKind::PayloadTooLarge(ref e)
+            | ErrorKind::Unprocessable(ref e)
+            | ErrorKind::RateLimited { error: ref e, .. }
+            | ErrorKind::ServerError(ref e)
+            | ErrorKind::Api(ref e) => Some(e),
+            ErrorKind::PartialWrite(ref e) => Some(&e.error),
+            _ => None,
+        }
+    }
+
+    /// Rejected lines of a write, if InfluxDB named any
+    pub fn write_error(&self) -> Option<&WriteError> {
+        match self.inner {
+            ErrorKind::PartialWrite(ref e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// HTTP status, if the request got a response
+    pub fn status(&self) -> Option<u16> {
+        self.api_error().map(|e| e.status)
+    }
+
+    /// Whether sending the same request again may succeed
+    ///
+    /// True for rate limiting, server errors other than 501 Not Implemented,
+    /// connection failures and timeouts.
+    pub fn is_retryable(&self) -> bool {
+        match self.inner {
+            ErrorKind::RateLimited { .. } => true,
+            ErrorKind::ServerError(ref e) => e.status != 501,
+            ErrorKind::Transport { retryable, .. } => retryable,
+            _ => false,
+        }
+    }
+}
+
 impl std::error::Error for Error {}
 
 impl std::error::Error for ErrorKind {}
@@ -64,8 +268,17 @@ impl From<io::Error> for Error {
 
 impl From<reqwest::Error> for Error {
     fn from(err: reqwest::Error) -> Self {
+        if err.is_decode() {
+            return Error {
+                inner: ErrorKind::SyntaxError(format!("{}", err))
+            };
+        }
+
         Error {
-            inner: ErrorKind::Communication(format!("{}", err))
+            inner: ErrorKind::Transport {
+                message: format!("{}", err),
+                retryable: retry::is_retryable_error(&err),
+            }
         }
     }
 }
@@ -78,14 +291,7 @@ impl Future for Error {
     // method we'll need to call to drive futures to completion.
     fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
 
-        match self.inner {
-            ErrorKind::SyntaxError(ref t) => Poll::Ready(format!("{}", t).to_string()),
-            ErrorKind::InvalidCredentials(ref t) => Poll::Ready(format!("{}", t).to_string()),
-            ErrorKind::DataBaseDoesNotExist(ref t) => Poll::Ready(format!("{}", t).to_string()),
-            ErrorKind::RetentionPolicyDoesNotExist(ref t) => Poll::Ready(format!("{}", t).to_string()),
-            ErrorKind::Communication(ref t) => Poll::Ready(format!("{}", t).to_string()),
-            ErrorKind::Unknown(ref t) => Poll::Ready(format!("{}", t).to_string()),
-        }        
+        Poll::Ready(self.inner.to_string())
     }
 
 }
@@ -93,14 +299,13 @@ impl Future for Error {
 #[cfg(test)]
 mod tests {
 
-    use crate::serialization;
-    use crate::{Error, error::ErrorKind};
+    use crate::{Error, error::{ApiError, ErrorKind}};
+    use std::time::Duration;
 
     #[test]
     fn syntax_error() {
         let syntax = Error {
-            inner: ErrorKind::SyntaxError(serialization::conversion(
-            "BAD SYNTAX",)),
+            inner: ErrorKind::SyntaxError("BAD SYNTAX".to_string()),
         };
 
         match syntax.inner {
@@ -112,4 +317,45 @@ mod tests {
 
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn parse_v2_error_body() {
+        let body = r#"{"code":"not found","message":"bucket \"missing\" not found","op":"influxdb/buckets"}"#;
+        let error = ApiError::parse(404, "POST", "/api/v2/write", body);
+
+        assert_eq!(error.code.as_deref(), Some("not found"));
+        assert_eq!(error.message, r#"bucket "missing" not found"#);
+        assert_eq!(error.op.as_deref(), Some("influxdb/buckets"));
+        assert_eq!(error.to_string(), r#"POST /api/v2/write: 404 not found: bucket "missing" not found"#);
+
+        let error = Error::from_status(error, None);
+        assert!(matches!(error.inner, ErrorKind::NotFound(_)));
+        assert_eq!(error.status(), Some(404));
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn parse_v1_and_plain_bodies() {
+        let error = ApiError::parse(400, "POST", "/query", r#"{"error":"error parsing query"}"#);
+        assert_eq!(error.message, "error parsing query");
+        assert_eq!(error.code, None);
+
+        let error = ApiError::parse(502, "GET", "/ping", "Bad Gateway\n");
+        assert_eq!(error.message, "Bad Gateway");
+    }
+
+    #[test]
+    fn retryable_statuses() {
+        let error = |status| ApiError::parse(status, "GET", "/", "");
+
+        let limited = Error::from_status(error(429), Some(Duration::from_secs(3)));
+        assert!(limited.is_retryable());
+        assert!(matches!(limited.inner, ErrorKind::RateLimited { retry_after: Some(d), .. } if d == Duration::from_secs(3)));
+
+        assert!(Error::from_status(error(503), None).is_retryable());
+        assert!(!Error::from_status(error(501), None).is_retryable());
+        assert!(matches!(Error::from_status(error(413), None).inner, ErrorKind::PayloadTooLarge(_)));
+        assert!(matches!(Error::from_status(error(422), None).inner, ErrorKind::Unprocessable(_)));
+        assert!(matches!(Error::from_status(error(409), None).inner, ErrorKind::Api(_)));
+    }
+}
@@ -0,0 +1,454 @@
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use std::fmt;
+
+use crate::data_model::query::{ReadQuery, Type};
+use crate::serialization::quote_ident;
+use crate::Value;
+
+/// Start or stop of a `range`
+#[derive(Debug, Clone, PartialEq)]
+pub enum FluxTime {
+    /// Absolute point in time
+    Absolute(DateTime<Utc>),
+    /// Relative to now, usually negative e.g. `-1h`
+    Relative(Duration),
+}
+
+impl From<DateTime<Utc>> for FluxTime {
+    fn from(t: DateTime<Utc>) -> Self {
+        FluxTime::Absolute(t)
+    }
+}
+
+impl From<Duration> for FluxTime {
+    fn from(d: Duration) -> Self {
+        FluxTime::Relative(d)
+    }
+}
+
+impl fmt::Display for FluxTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FluxTime::Absolute(t) => write!(f, "{}", t.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+            FluxTime::Relative(d) => write!(f, "{}", duration_literal(d)),
+        }
+    }
+}
+
+/// Render a duration literal such as `-1h30m` or `1s500ms`
+pub(crate) fn duration_literal(d: &Duration) -> String {
+    let nanos = d.num_nanoseconds().map(i128::from).unwrap_or_else(|| {
+        d.num_microseconds()
+            .map(|us| i128::from(us) * 1_000)
+            .unwrap_or_else(|| i128::from(d.num_milliseconds()) * 1_000_000)
+    });
+
+    if nanos == 0 {
+        return "0s".to_string();
+    }
+
+    let mut literal = String::new();
+    if nanos < 0 {
+        literal.push('-');
+    }
+
+    let mut rest = nanos.unsigned_abs();
+    for (unit, size) in [
+        ("h", 3_600_000_000_000u128),
+        ("m", 60_000_000_000),
+        ("s", 1_000_000_000),
+        ("ms", 1_000_000),
+        ("us", 1_000),
+        ("ns", 1),
+    ] {
+        if rest >= size {
+            literal.push_str(&format!("{}{}", rest / size, unit));
+            rest %= size;
+        }
+    }
+
+    literal
+}
+
+/// Render a value as a Flux literal, escaping strings
+fn value_literal(value: &Value) -> String {
+    match value {
+        Value::String(s) => quote_ident(s),
+        Value::Integer(i) => i.to_string(),
+        Value::UInteger(u) => format!("uint(v: {})", u),
+        Value::Float(f) if f.is_nan() => "float(v: \"NaN\")".to_string(),
+        Value::Float(f) if f.is_infinite() => {
+            format!("float(v: \"{}Inf\")", if *f > 0.0 { "+" } else { "-" })
+        }
+        Value::Float(f) => {
+            let s = f.to_string();
+            if s.contains('.') {
+                s
+            } else {
+                format!("{}.0", s)
+            }
+        }
+        Value::Boolean(b) => b.to_string(),
+    }
+}
+
+fn column(name: &str) -> String {
+    format!("r[{}]", quote_ident(name))
+}
+
+fn string_array(values: &[&str]) -> String {
+    let quoted: Vec<String> = values.iter().map(|v| quote_ident(v)).collect();
+    format!("[{}]", quoted.join(", "))
+}
+
+/// Predicate used by `FluxQuery::filter`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Predicate {
+    /// Compare a column to a value with the given operator
+    Compare(String, &'static str, Value<'static>),
+    /// Both predicates hold
+    And(Box<Predicate>, Box<Predicate>),
+    /// Either predicate holds
+    Or(Box<Predicate>, Box<Predicate>),
+    /// Predicate does not hold
+    Not(Box<Predicate>),
+}
+
+impl Predicate {
+    /// `r._measurement == measurement`
+    pub fn measurement<T: Into<String>>(measurement: T) -> Self {
+        Predicate::eq("_measurement", Value::from(measurement.into()))
+    }
+
+    /// `r._field == field`
+    pub fn field<T: Into<String>>(field: T) -> Self {
+        Predicate::eq("_field", Value::from(field.into()))
+    }
+
+    /// `r[tag] == value`
+    pub fn tag<T: Into<String>>(tag: &str, value: T) -> Self {
+        Predicate::eq(tag, Value::from(value.into()))
+    }
+
+    /// `r[column] == value`
+    pub fn eq<V: Into<Value<'static>>>(column: &str, value: V) -> Self {
+        Predicate::Compare(column.to_string(), "==", value.into())
+    }
+
+    /// `r[column] != value`
+    pub fn ne<V: Into<Value<'static>>>(column: &str, value: V) -> Self {
+        Predicate::Compare(column.to_string(), "!=", value.into())
+    }
+
+    /// `r[column] > value`
+    pub fn gt<V: Into<Value<'static>>>(column: &str, value: V) -> Self {
+        Predicate::Compare(column.to_string(), ">", value.into())
+    }
+
+    /// `r[column] >= value`
+    pub fn ge<V: Into<Value<'static>>>(column: &str, value: V) -> Self {
+        Predicate::Compare(column.to_string(), ">=", value.into())
+    }
+
+    /// `r[column] < value`
+    pub fn lt<V: Into<Value<'static>>>(column: &str, value: V) -> Self {
+        Predicate::Compare(column.to_string(), "<", value.into())
+    }
+
+    /// `r[column] <= value`
+    pub fn le<V: Into<Value<'static>>>(column: &str, value: V) -> Self {
+        Predicate::Compare(column.to_string(), "<=", value.into())
+    }
+
+    /// `self and other`
+    pub fn and(self, other: Predicate) -> Self {
+        Predicate::And(Box::new(self), Box::new(other))
+    }
+
+    /// `self or other`
+    pub fn or(self, other: Predicate) -> Self {
+        Predicate::Or(Box::new(self), Box::new(other))
+    }
+
+    /// `not self`
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Predicate::Not(Box::new(self))
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Predicate::Compare(name, op, value) => {
+                write!(f, "{} {} {}", column(name), op, value_literal(value))
+            }
+            Predicate::And(a, b) => write!(f, "({} and {})", a, b),
+            Predicate::Or(a, b) => write!(f, "({} or {})", a, b),
+            Predicate::Not(a) => write!(f, "not ({})", a),
+        }
+    }
+}
+
+/// Aggregate function used by `aggregateWindow`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    /// mean
+    Mean,
+    /// median
+    Median,
+    /// sum
+    Sum,
+    /// count
+    Count,
+    /// min
+    Min,
+    /// max
+    Max,
+    /// first
+    First,
+    /// last
+    Last,
+}
+
+impl fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Aggregate::Mean => write!(f, "mean"),
+            Aggregate::Median => write!(f, "median"),
+            Aggregate::Sum => write!(f, "sum"),
+            Aggregate::Count => write!(f, "count"),
+            Aggregate::Min => write!(f, "min"),
+            Aggregate::Max => write!(f, "max"),
+            Aggregate::First => write!(f, "first"),
+            Aggregate::Last => write!(f, "last"),
+        }
+    }
+}
+
+/// Builder for Flux queries
+///
+/// Every string given to the builder is escaped, so values coming from users
+/// can be used safely. The only exception is `map`, whose function body is
+/// inserted verbatim.
+///
+/// ```
+/// use influxdb_rs::flux_query::{FluxQuery, Predicate};
+/// use chrono::Duration;
+///
+/// let query = FluxQuery::from("test_bucket")
+///     .range(Duration::hours(-1), None)
+///     .filter(Predicate::measurement("cpu").and(Predicate::tag("host", "server01")))
+///     .limit(10)
+///     .to_string();
+///
+/// assert_eq!(query, "from(bucket: \"test_bucket\")
+///   |> range(start: -1h)
+///   |> filter(fn: (r) => (r[\"_measurement\"] == \"cpu\" and r[\"host\"] == \"server01\"))
+///   |> limit(n: 10)");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FluxQuery {
+    bucket: String,
+    stages: Vec<String>,
+}
+
+impl FluxQuery {
+    /// `from(bucket: bucket)`
+    #[allow(clippy::should_implement_trait)]
+    pub fn from<T: Into<String>>(bucket: T) -> Self {
+        FluxQuery {
+            bucket: bucket.into(),
+            stages: Vec::new(),
+        }
+    }
+
+    fn pipe(mut self, stage: String) -> Self {
+        self.stages.push(stage);
+        self
+    }
+
+    /// `range(start: start, stop: stop)`
+    pub fn range<S: Into<FluxTime>>(self, start: S, stop: Option<FluxTime>) -> Self {
+        let stage = match stop {
+            Some(stop) => format!("range(start: {}, stop: {})", start.into(), stop),
+            None => format!("range(start: {})", start.into()),
+        };
+        self.pipe(stage)
+    }
+
+    /// `filter(fn: (r) => predicate)`
+    pub fn filter(self, predicate: Predicate) -> Self {
+        self.pipe(format!("filter(fn: (r) => {})", predicate))
+    }
+
+    /// `aggregateWindow(every: every, fn: aggregate, createEmpty: create_empty)`
+    pub fn aggregate_window(self, every: Duration, aggregate: Aggregate, create_empty: bool) -> Self {
+        self.pipe(format!(
+            "aggregateWindow(every: {}, fn: {}, createEmpty: {})",
+            duration_literal(&every),
+            aggregate,
+            create_empty
+        ))
+    }
+
+    /// `group(columns: columns)`
+    pub fn group(self, columns: &[&str]) -> Self {
+        self.pipe(format!("group(columns: {})", string_array(columns)))
+    }
+
+    /// `group(columns: columns, mode: "except")`
+    pub fn group_except(self, columns: &[&str]) -> Self {
+        self.pipe(format!("group(columns: {}, mode: \"except\")", string_array(columns)))
+    }
+
+    /// `pivot(rowKey: row_key, columnKey: column_key, valueColumn: value_column)`
+    pub fn pivot(self, row_key: &[&str], column_key: &[&str], value_column: &str) -> Self {
+        self.pipe(format!(
+            "pivot(rowKey: {}, columnKey: {}, valueColumn: {})",
+            string_array(row_key),
+            string_array(column_key),
+            quote_ident(value_column)
+        ))
+    }
+
+    /// `sort(columns: columns, desc: desc)`
+    pub fn sort(self, columns: &[&str], desc: bool) -> Self {
+        self.pipe(format!("sort(columns: {}, desc: {})", string_array(columns), desc))
+    }
+
+    /// `limit(n: n)`
+    pub fn limit(self, n: u64) -> Self {
+        self.pipe(format!("limit(n: {})", n))
+    }
+
+    /// `limit(n: n, offset: offset)`
+    pub fn limit_offset(self, n: u64, offset: u64) -> Self {
+        self.pipe(format!("limit(n: {}, offset: {})", n, offset))
+    }
+
+    /// `keep(columns: columns)`
+    pub fn keep(self, columns: &[&str]) -> Self {
+        self.pipe(format!("keep(columns: {})", string_array(columns)))
+    }
+
+    /// `drop(columns: columns)`
+    pub fn drop(self, columns: &[&str]) -> Self {
+        self.pipe(format!("drop(columns: {})", string_array(columns)))
+    }
+
+    /// `map(fn: (r) => body)`
+    ///
+    /// The body is inserted verbatim, e.g. `({ r with _value: r._value * 2.0 })`.
+    /// Never build it from untrusted input.
+    pub fn map(self, body: &str) -> Self {
+        self.pipe(format!("map(fn: (r) => {})", body))
+    }
+
+    /// `yield(name: name)`
+    pub fn yield_as(self, name: &str) -> Self {
+        self.pipe(format!("yield(name: {})", quote_ident(name)))
+    }
+
+    /// Build the `ReadQuery` to pass to `Client::query`
+    pub fn build(&self) -> ReadQuery {
+        ReadQuery {
+            query: self.to_string(),
+            r#type: Some(Type::Flux),
+            ..Default::default()
+        }
+    }
+}
+
+impl fmt::Display for FluxQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "from(bucket: {})", quote_ident(&self.bucket))?;
+        for stage in &self.stages {
+            write!(f, "\n  |> {}", stage)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<FluxQuery> for ReadQuery {
+    fn from(query: FluxQuery) -> Self {
+        query.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn full_query() {
+        let start = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let stop = start + Duration::nanoseconds(1_500);
+
+        let query = FluxQuery::from("telemetry")
+            .range(start, Some(stop.into()))
+            .filter(Predicate::measurement("cpu"))
+            .filter(Predicate::field("usage").or(Predicate::gt("_value", 1.0).not()))
+            .aggregate_window(Duration::minutes(90), Aggregate::Mean, false)
+            .group(&["host"])
+            .pivot(&["_time"], &["_field"], "_value")
+            .sort(&["_time"], true)
+            .limit_offset(10, 5)
+            .keep(&["_time", "host"])
+            .drop(&["_start"])
+            .map("({ r with doubled: r._value * 2.0 })")
+            .yield_as("result");
+
+        assert_eq!(
+            query.to_string(),
+            "from(bucket: \"telemetry\")
+  |> range(start: 2022-01-01T00:00:00Z, stop: 2022-01-01T00:00:00.000001500Z)
+  |> filter(fn: (r) => r[\"_measurement\"] == \"cpu\")
+  |> filter(fn: (r) => (r[\"_field\"] == \"usage\" or not (r[\"_value\"] > 1.0)))
+  |> aggregateWindow(every: 1h30m, fn: mean, createEmpty: false)
+  |> group(columns: [\"host\"])
+  |> pivot(rowKey: [\"_time\"], columnKey: [\"_field\"], valueColumn: \"_value\")
+  |> sort(columns: [\"_time\"], desc: true)
+  |> limit(n: 10, offset: 5)
+  |> keep(columns: [\"_time\", \"host\"])
+  |> drop(columns: [\"_start\"])
+  |> map(fn: (r) => ({ r with doubled: r._value * 2.0 }))
+  |> yield(name: \"result\")"
+        );
+
+        let read = query.build();
+        assert_eq!(read.query, query.to_string());
+        assert_eq!(read.r#type, Some(Type::Flux));
+    }
+
+    #[test]
+    fn escapes_user_input() {
+        let query = FluxQuery::from("b\"ucket")
+            .filter(Predicate::tag("host", "\") |> drop(columns: [\"x\"]) // ${token} \\"));
+
+        assert_eq!(
+            query.to_string(),
+            "from(bucket: \"b\\\"ucket\")
+  |> filter(fn: (r) => r[\"host\"] == \"\\\") |> drop(columns: [\\\"x\\\"]) // \\${token} \\\\\")"
+        );
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(value_literal(&Value::Float(2.0)), "2.0");
+        assert_eq!(value_literal(&Value::Float(2.5)), "2.5");
+        assert_eq!(value_literal(&Value::Float(f64::INFINITY)), "float(v: \"+Inf\")");
+        assert_eq!(value_literal(&Value::Integer(-3)), "-3");
+        assert_eq!(value_literal(&Value::UInteger(3)), "uint(v: 3)");
+        assert_eq!(value_literal(&Value::Boolean(true)), "true");
+
+        assert_eq!(duration_literal(&Duration::zero()), "0s");
+        assert_eq!(duration_literal(&Duration::hours(-1)), "-1h");
+        assert_eq!(duration_literal(&Duration::milliseconds(61_001)), "1m1s1ms");
+
+        // Past the nanosecond and the microsecond range of i64
+        assert_eq!(duration_literal(&Duration::hours(1_000_000)), "1000000h");
+        assert_eq!(duration_literal(&Duration::hours(-4_000_000_000)), "-4000000000h");
+    }
+}
@@ -49,7 +49,7 @@
 //!     let flux_query = format!("from(bucket: \"test_bucket\") 
 //!         |> range(start: time(v: {:?}))
 //!         |> filter(fn: (r) => r._measurement == \"test4\")
-//!         |> yield()", now.timestamp_nanos());
+//!         |> yield()", now.timestamp_nanos_opt().unwrap());
 //! 
 //! 
 //!     let query = influxdb_rs::data_model::query::ReadQuery{
@@ -58,6 +58,7 @@
 //!         r#type: None,
 //!         dialect: None,
 //!        now: None,
+//!        params: None,
 //! 
 //!     };
 //! 
@@ -75,8 +76,23 @@
 
 /// API Functions are implemented for influxdb_rs::client::Client
 pub mod client;
+/// Client construction with timeouts, TLS and proxy options
+pub mod builder;
 /// Error module
 pub mod error;
+/// Background batching writer
+pub mod batch;
+/// Retry policy for writes and queries
+pub mod retry;
+/// Rejected lines of partial writes
+pub mod write_error;
+/// Token providers consulted for every request
+pub mod credentials;
+/// Type-safe Flux query builder
+pub mod flux_query;
+
+/// Line protocol encoding into reusable buffers and parsing back into points
+pub mod line_protocol;
 
 /// Serialization module
 pub(crate) mod serialization;
@@ -88,8 +104,14 @@ pub mod data_model;
 #[doc(hidden)]
 pub mod api;
 
-pub use client::Client;
-pub use error::Error;
+pub use client::{Client, Compression, WriteMode};
+pub use builder::ClientBuilder;
+pub use batch::{BatchError, BatchOptions, BatchWriter};
+pub use retry::RetryPolicy;
+pub use credentials::{CredentialsProvider, EnvToken, FileToken, SharedToken, StaticToken};
+pub use flux_query::{FluxQuery, Predicate};
+pub use error::{ApiError, Error};
+pub use write_error::{RejectReason, RejectedLine, WriteError};
 pub use data_model::data_points::{Point, Points, Precision, Value};
 
 pub use reqwest;
@@ -0,0 +1,628 @@
+use bytes::{BufMut, BytesMut};
+use std::borrow::{Borrow, Cow};
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::{error, Point, Value};
+
+/// Append a point as a line of line protocol to `buf`
+///
+/// The buffer can be reused across batches: `buf.split().freeze()` hands out the
+/// encoded batch and keeps the allocation once the batch is dropped.
+///
+/// ```
+/// use bytes::BytesMut;
+/// use influxdb_rs::{line_protocol, Point};
+///
+/// let mut buf = BytesMut::with_capacity(1024);
+/// line_protocol::encode_point(&Point::new("cpu").add_field("value", 1), &mut buf);
+/// assert_eq!(&buf[..], b"cpu value=1i\n");
+/// ```
+pub fn encode_point(point: &Point, buf: &mut BytesMut) {
+    // Writing into a BytesMut cannot fail
+    let _ = write_point(point, &mut buf.writer());
+}
+
+/// Append points as line protocol to `buf`, one line per point
+pub fn encode_points<'a>(points: impl IntoIterator<Item = impl Borrow<Point<'a>>>, buf: &mut BytesMut) {
+    let mut writer = buf.writer();
+    for point in points {
+        let _ = write_point(point.borrow(), &mut writer);
+    }
+}
+
+/// Write a point as a line of line protocol
+///
+/// Values are written in small pieces, wrap files and sockets in an `io::BufWriter`.
+pub fn write_point<W: Write>(point: &Point, writer: &mut W) -> io::Result<()> {
+    write_escaped(writer, &point.measurement, b", ")?;
+
+    for (tag, value) in &point.tags {
+        writer.write_all(b",")?;
+        write_escaped(writer, tag, b",= ")?;
+        writer.write_all(b"=")?;
+
+        match value {
+            Value::String(s) => write_escaped(writer, s, b",= ")?,
+            Value::Float(f) => write!(writer, "{}", f)?,
+            Value::Integer(i) => write!(writer, "{}", i)?,
+            Value::UInteger(u) => write!(writer, "{}", u)?,
+            Value::Boolean(b) => write_bool(writer, *b)?,
+        }
+    }
+
+    let mut separator = b" ";
+    for (field, value) in &point.fields {
+        writer.write_all(separator)?;
+        separator = b",";
+
+        write_escaped(writer, field, b",= ")?;
+        writer.write_all(b"=")?;
+
+        match value {
+            Value::String(s) => {
+                writer.write_all(b"\"")?;
+                write_escaped(writer, s, b"\"\\")?;
+                writer.write_all(b"\"")?;
+            }
+            Value::Float(f) => write!(writer, "{}", f)?,
+            Value::Integer(i) => write!(writer, "{}i", i)?,
+            Value::UInteger(u) => write!(writer, "{}u", u)?,
+            Value::Boolean(b) => write_bool(writer, *b)?,
+        }
+    }
+
+    if let Some(t) = point.timestamp {
+        write!(writer, " {}", t)?;
+    }
+
+    writer.write_all(b"\n")
+}
+
+/// Write points as line protocol, one line per point
+pub fn write_points<'a, W: Write>(points: impl IntoIterator<Item = impl Borrow<Point<'a>>>, writer: &mut W) -> io::Result<()> {
+    for point in points {
+        write_point(point.borrow(), writer)?;
+    }
+    Ok(())
+}
+
+/// Write `value`, putting a backslash in front of every byte in `special`
+#[inline]
+fn write_escaped<W: Write>(writer: &mut W, value: &str, special: &[u8]) -> io::Result<()> {
+    let bytes = value.as_bytes();
+    let mut start = 0;
+
+    for (i, b) in bytes.iter().enumerate() {
+        if special.contains(b) {
+            writer.write_all(&bytes[start..i])?;
+            writer.write_all(&[b'\\', *b])?;
+            start = i + 1;
+        }
+    }
+
+    writer.write_all(&bytes[start..])
+}
+
+#[inline]
+fn write_bool<W: Write>(writer: &mut W, value: bool) -> io::Result<()> {
+    writer.write_all(if value { b"true" } else { b"false" })
+}
+
+/// Error in line protocol text, with the position it was found at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line of the input, starting at 1
+    pub line: usize,
+    /// Character of the line, starting at 1
+    pub column: usize,
+    /// What is wrong
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for error::Error {
+    fn from(err: ParseError) -> Self {
+        error::Error {
+            inner: error::ErrorKind::SyntaxError(err.to_string()),
+        }
+    }
+}
+
+/// Parse line protocol into points, stopping at the first invalid line
+///
+/// Blank lines and lines starting with `#` are skipped. Tag values are always
+/// parsed as strings, string field values borrow from `input` when they hold no escapes.
+///
+/// ```
+/// use influxdb_rs::{line_protocol, Value};
+///
+/// let points = line_protocol::parse("# telegraf\ncpu,host=a usage=0.5,count=3i 1700000000\n").unwrap();
+/// assert_eq!(points[0].fields["count"], Value::Integer(3));
+///
+/// let err = line_protocol::parse("cpu usage=0.5\ncpu usage=\n").unwrap_err();
+/// assert_eq!((err.line, err.column), (2, 11));
+/// ```
+pub fn parse(input: &str) -> Result<Vec<Point<'_>>, ParseError> {
+    parse_lines(input).collect()
+}
+
+/// Parse line protocol point by point, carrying on after invalid lines
+pub fn parse_lines(input: &str) -> Lines<'_> {
+    Lines {
+        parser: Parser {
+            input,
+            pos: 0,
+            line: 1,
+            line_start: 0,
+        },
+    }
+}
+
+/// Iterator over the points of line protocol text, see `parse_lines`
+#[derive(Debug, Clone)]
+pub struct Lines<'a> {
+    parser: Parser<'a>,
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Result<Point<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_record().map(|(_, point)| point)
+    }
+}
+
+impl<'a> Lines<'a> {
+    /// Next point together with the text it was parsed from, without the line break
+    fn next_record(&mut self) -> Option<(&'a str, Result<Point<'a>, ParseError>)> {
+        let parser = &mut self.parser;
+
+        loop {
+            parser.skip(|b| b == b' ' || b == b'\t' || b == b'\r');
+            match parser.peek() {
+                None => return None,
+                Some(b'\n') => parser.newline(),
+                Some(b'#') => parser.skip_line(),
+                Some(_) => break,
+            }
+        }
+
+        let start = parser.pos;
+        let point = parser.point();
+        if point.is_err() {
+            parser.skip_line();
+        }
+
+        let text = parser.input[start..parser.pos].trim_end_matches(['\n', '\r']);
+        Some((text, point))
+    }
+}
+
+/// Every point of `input` with the text it was parsed from
+///
+/// A string field may hold line breaks, so one record can span several lines.
+pub(crate) fn records(input: &str) -> Vec<(&str, Result<Point<'_>, ParseError>)> {
+    let mut lines = parse_lines(input);
+    std::iter::from_fn(|| lines.next_record()).collect()
+}
+
+#[derive(Debug, Clone)]
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    line: usize,
+    line_start: usize,
+}
+
+const MEASUREMENT: &[u8] = b", ";
+const KEY: &[u8] = b",= ";
+
+impl<'a> Parser<'a> {
+    fn point(&mut self) -> Result<Point<'a>, ParseError> {
+        let mut point = Point::new(self.ident(MEASUREMENT, "measurement")?);
+
+        while self.peek() == Some(b',') {
+            self.pos += 1;
+            let key = self.ident(KEY, "tag key")?;
+            self.expect(b'=')?;
+            let value = self.ident(KEY, "tag value")?;
+            point.tags.insert(key.into_owned(), Value::String(value));
+        }
+
+        if self.peek() != Some(b' ') {
+            return Err(self.error(self.pos, "expected fields"));
+        }
+        self.skip(|b| b == b' ');
+
+        loop {
+            let key = self.ident(KEY, "field key")?;
+            self.expect(b'=')?;
+            let value = self.field_value()?;
+            point.fields.insert(key.into_owned(), value);
+
+            if self.peek() != Some(b',') {
+                break;
+            }
+            self.pos += 1;
+        }
+
+        self.skip(|b| b == b' ');
+        if matches!(self.peek(), Some(b) if b == b'-' || b.is_ascii_digit()) {
+            let start = self.pos;
+            let token = self.token();
+            let timestamp = token.parse().map_err(|_| self.error(start, "invalid timestamp"))?;
+            point.timestamp = Some(timestamp);
+        }
+
+        self.skip(|b| b == b' ' || b == b'\t' || b == b'\r');
+        match self.peek() {
+            None => Ok(point),
+            Some(b'\n') => {
+                self.newline();
+                Ok(point)
+            }
+            Some(_) => Err(self.error(self.pos, "expected end of line")),
+        }
+    }
+
+    /// Measurement, key or tag value, ending at an unescaped byte of `special`
+    fn ident(&mut self, special: &[u8], what: &str) -> Result<Cow<'a, str>, ParseError> {
+        let bytes = self.input.as_bytes();
+        let start = self.pos;
+        let mut escaped = false;
+
+        while let Some(&b) = bytes.get(self.pos) {
+            if b == b'\\' && bytes.get(self.pos + 1).is_some_and(|next| special.contains(next)) {
+                escaped = true;
+                self.pos += 2;
+                continue;
+            }
+            if b == b'\n' || special.contains(&b) {
+                break;
+            }
+            self.pos += 1;
+        }
+
+        if self.pos == start {
+            return Err(self.error(start, &format!("expected {}", what)));
+        }
+
+        let raw = &self.input[start..self.pos];
+        Ok(if escaped { Cow::Owned(unescape(raw, special)) } else { Cow::Borrowed(raw) })
+    }
+
+    fn field_value(&mut self) -> Result<Value<'a>, ParseError> {
+        let start = self.pos;
+
+        if self.peek() == Some(b'"') {
+            return self.string_value();
+        }
+
+        let token = self.token();
+        let invalid = |parser: &Self, kind: &str| parser.error(start, &format!("invalid {} field value", kind));
+
+        match token.as_bytes().last() {
+            None => Err(self.error(start, "expected field value")),
+            Some(b'i') => token[..token.len() - 1].parse().map(Value::Integer).map_err(|_| invalid(self, "integer")),
+            Some(b'u') => token[..token.len() - 1].parse().map(Value::UInteger).map_err(|_| invalid(self, "unsigned")),
+            _ => match token {
+                "t" | "T" | "true" | "True" | "TRUE" => Ok(Value::Boolean(true)),
+                "f" | "F" | "false" | "False" | "FALSE" => Ok(Value::Boolean(false)),
+                _ => {
+                    let numeric = token.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+' || c == '.');
+                    match token.parse::<f64>() {
+                        Ok(f) if numeric && f.is_finite() => Ok(Value::Float(f)),
+                        _ => Err(invalid(self, "float")),
+                    }
+                }
+            },
+        }
+    }
+
+    /// Double quoted string, may span several lines
+    fn string_value(&mut self) -> Result<Value<'a>, ParseError> {
+        let bytes = self.input.as_bytes();
+        let (start, start_line, start_line_start) = (self.pos, self.line, self.line_start);
+        self.pos += 1;
+
+        let mut escaped = false;
+        loop {
+            match bytes.get(self.pos) {
+                None => {
+                    self.line = start_line;
+                    self.line_start = start_line_start;
+                    return Err(self.error(start, "unterminated string"));
+                }
+                Some(b'\\') if matches!(bytes.get(self.pos + 1), Some(b'"') | Some(b'\\')) => {
+                    escaped = true;
+                    self.pos += 2;
+                }
+                Some(b'"') => break,
+                Some(b'\n') => self.newline(),
+                Some(_) => self.pos += 1,
+            }
+        }
+
+        let raw = &self.input[start + 1..self.pos];
+        self.pos += 1;
+
+        Ok(Value::String(if escaped { Cow::Owned(unescape(raw, b"\"\\")) } else { Cow::Borrowed(raw) }))
+    }
+
+    /// Unquoted field value or timestamp
+    fn token(&mut self) -> &'a str {
+        let start = self.pos;
+        self.skip(|b| !matches!(b, b',' | b' ' | b'\n' | b'\r' | b'\t'));
+        &self.input[start..self.pos]
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), ParseError> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(self.pos, &format!("expected '{}'", byte as char)))
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip(&mut self, mut while_: impl FnMut(u8) -> bool) {
+        while self.peek().is_some_and(&mut while_) {
+            self.pos += 1;
+        }
+    }
+
+    fn newline(&mut self) {
+        self.pos += 1;
+        self.line += 1;
+        self.line_start = self.pos;
+    }
+
+    fn skip_line(&mut self) {
+        self.skip(|b| b != b'\n');
+        if self.peek().is_some() {
+            self.newline();
+        }
+    }
+
+    fn error(&self, pos: usize, message: &str) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.input[self.line_start..pos].chars().count() + 1,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Drop the backslash in front of every byte in `special`
+fn unescape(raw: &str, special: &[u8]) -> String {
+    let bytes = raw.as_bytes();
+    let mut out = String::with_capacity(raw.len());
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && bytes.get(i + 1).is_some_and(|next| special.contains(next)) {
+            out.push_str(&raw[start..i]);
+            start = i + 1;
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+
+    out.push_str(&raw[start..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Points;
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+
+    fn escaped(value: &str, special: &[u8]) -> String {
+        let mut out = Vec::new();
+        write_escaped(&mut out, value, special).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn encode_points_test() {
+        let point = Point::new("test")
+            .add_field("somefield", Value::Integer(65))
+            .add_tag("sometag", Value::Boolean(false));
+        let points = Points::new(point);
+
+        let mut buf = BytesMut::new();
+        encode_points(&points, &mut buf);
+        assert_eq!(&buf[..], b"test,sometag=false somefield=65i\n");
+    }
+
+    #[test]
+    fn write_point_test() {
+        let point = Point::new("cpu load")
+            .add_field("value", 0.5)
+            .add_field("host", "a \"b\"")
+            .add_timestamp(1_700_000_000);
+
+        let mut out = Vec::new();
+        write_point(&point, &mut out).unwrap();
+        let line = String::from_utf8(out).unwrap();
+
+        assert!(line.starts_with("cpu\\ load "));
+        assert!(line.contains("value=0.5"));
+        assert!(line.contains("host=\"a \\\"b\\\"\""));
+        assert!(line.ends_with(" 1700000000\n"));
+    }
+
+    #[test]
+    fn reuse_buffer() {
+        let mut buf = BytesMut::with_capacity(64);
+
+        encode_point(&Point::new("a").add_field("v", true), &mut buf);
+        let first = buf.split().freeze();
+        encode_point(&Point::new("b").add_field("v", false), &mut buf);
+
+        assert_eq!(&first[..], b"a v=true\n");
+        assert_eq!(&buf[..], b"b v=false\n");
+    }
+
+    #[test]
+    fn escape_keys_and_tags_test() {
+        assert_eq!(
+            escaped("foo, hello=world", b",= "),
+            "foo\\,\\ hello\\=world"
+        )
+    }
+
+    #[test]
+    fn escape_measurement_test() {
+        assert_eq!(escaped("foo, hello", b", "), "foo\\,\\ hello")
+    }
+
+    #[test]
+    fn escape_string_field_value_test() {
+        assert_eq!(escaped("\"foo", b"\"\\"), "\\\"foo");
+        assert_eq!(escaped("C:\\dir\\", b"\"\\"), "C:\\\\dir\\\\");
+    }
+
+    #[test]
+    fn parse_telegraf_export() {
+        let input = "# DML\n\
+            # CONTEXT-DATABASE: telegraf\n\
+            \n\
+            cpu,cpu=cpu-total,host=my\\ host usage_idle=98.5,usage_user=1.25 1700000000000000000\r\n\
+            \n\
+            disk,path=/,fstype=ext4 used=1024u,inodes_free=-3i,ok=T,label=\"root \\\"fs\\\" C:\\\\\" \n\
+            weather\\,eu temp=21\n";
+
+        let points = parse(input).unwrap();
+        assert_eq!(points.len(), 3);
+
+        assert_eq!(points[0].measurement, "cpu");
+        assert_eq!(points[0].tags["host"], Value::String("my host".into()));
+        assert_eq!(points[0].fields["usage_user"], Value::Float(1.25));
+        assert_eq!(points[0].timestamp, Some(1_700_000_000_000_000_000));
+
+        assert_eq!(points[1].tags["path"], Value::String("/".into()));
+        assert_eq!(points[1].fields["used"], Value::UInteger(1024));
+        assert_eq!(points[1].fields["inodes_free"], Value::Integer(-3));
+        assert_eq!(points[1].fields["ok"], Value::Boolean(true));
+        assert_eq!(points[1].fields["label"], Value::String("root \"fs\" C:\\".into()));
+        assert_eq!(points[1].timestamp, None);
+
+        assert_eq!(points[2].measurement, "weather,eu");
+        assert_eq!(points[2].fields["temp"], Value::Float(21.0));
+    }
+
+    #[test]
+    fn strings_borrow_and_span_lines() {
+        let points = parse("log msg=\"first\nsecond\" 1\nlog msg=\"plain\" 2\n").unwrap();
+
+        assert_eq!(points[0].fields["msg"], Value::String("first\nsecond".into()));
+        assert!(matches!(points[1].fields["msg"], Value::String(Cow::Borrowed("plain"))));
+        assert_eq!(points[1].timestamp, Some(2));
+    }
+
+    #[test]
+    fn error_positions() {
+        let error = |input| parse(input).unwrap_err();
+
+        assert_eq!(error("cpu\n"), ParseError { line: 1, column: 4, message: "expected fields".to_string() });
+        assert_eq!(error("cpu value=1\n\ncpu,host value=1").column, 9);
+        assert_eq!(error("cpu value=1\n\ncpu,host value=1").line, 3);
+        assert_eq!(error("cpu value=12x").message, "invalid float field value");
+        assert_eq!(error("cpu value=1.5i").message, "invalid integer field value");
+        assert_eq!(error("cpu value=-1u").message, "invalid unsigned field value");
+        assert_eq!(error("cpu value=inf").message, "invalid float field value");
+        assert_eq!(error("cpu value=1 12:00").message, "invalid timestamp");
+        assert_eq!(error("cpu value=1 1 2").message, "expected end of line");
+        assert_eq!(error("cpu,=a value=1").message, "expected tag key");
+        assert_eq!(error("cpu value=\"open\n\n").column, 11);
+
+        // Columns count characters, not bytes
+        assert_eq!(error("température value=oui").column, 19);
+    }
+
+    #[test]
+    fn parse_lines_continues() {
+        let results: Vec<_> = parse_lines("a v=1\nb v=\"x\nc v=3\n").collect();
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert_eq!(results[1].as_ref().unwrap_err().line, 2);
+
+        let results: Vec<_> = parse_lines("a v=1\nb v=x\nc v=3\n").collect();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1].as_ref().unwrap_err().line, 2);
+        assert_eq!(results[2].as_ref().unwrap().measurement, "c");
+    }
+
+    /// Point that line protocol can represent
+    #[derive(Debug, Clone)]
+    struct Representable(Point<'static>);
+
+    fn ident(g: &mut Gen, first: &[char]) -> String {
+        const CHARS: &[char] = &['a', 'z', 'Q', '0', '9', '_', '-', ' ', ',', '=', '"', '\\', '#', 'é', '☃'];
+
+        let len = usize::arbitrary(g) % 8;
+        let mut ident: String = std::iter::once(*g.choose(first).unwrap())
+            .chain((0..len).map(|_| *g.choose(CHARS).unwrap()))
+            .collect();
+
+        // A trailing backslash would escape the delimiter after it
+        while ident.ends_with('\\') {
+            ident.pop();
+            ident.push('x');
+        }
+        ident
+    }
+
+    impl Arbitrary for Representable {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let mut point = Point::new(ident(g, &['m', 'Z', '1', ',', ' ', '=', '"', 'é']));
+
+            for _ in 0..usize::arbitrary(g) % 4 {
+                point.tags.insert(ident(g, &['t', '#', ' ', ',', '=']), Value::String(ident(g, &['v', ' ', ',', '=']).into()));
+            }
+
+            for _ in 0..1 + usize::arbitrary(g) % 4 {
+                let value = match u8::arbitrary(g) % 5 {
+                    0 => Value::String(String::arbitrary(g).into()),
+                    1 => Value::Integer(i64::arbitrary(g)),
+                    2 => Value::UInteger(u64::arbitrary(g)),
+                    3 => Value::Boolean(bool::arbitrary(g)),
+                    _ => Value::Float(Some(f64::arbitrary(g)).filter(|f| f.is_finite()).unwrap_or(0.5)),
+                };
+                point.fields.insert(ident(g, &['f', '#', ' ', ',', '=', '"']), value);
+            }
+
+            point.timestamp = Option::<i64>::arbitrary(g);
+            Representable(point)
+        }
+    }
+
+    quickcheck! {
+        fn round_trip(points: Vec<Representable>) -> bool {
+            let points: Vec<Point> = points.into_iter().map(|p| p.0).collect();
+
+            let mut buf = BytesMut::new();
+            encode_points(&points, &mut buf);
+
+            let text = std::str::from_utf8(&buf).unwrap();
+            parse(text).map(|parsed| parsed == points).unwrap_or(false)
+        }
+    }
+}
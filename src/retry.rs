@@ -0,0 +1,207 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, Instant};
+
+use reqwest::{header, RequestBuilder, Response, StatusCode};
+
+use crate::client::Client;
+use crate::error;
+
+/// Retry policy applied to writes and queries
+///
+/// Requests failing with 429, a 5xx status or a connection error are retried
+/// with exponential backoff. A `Retry-After` header sent by the server takes
+/// precedence over the computed delay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_delay: Duration,
+    /// Factor the delay is multiplied by after every retry
+    pub multiplier: f64,
+    /// Random fraction of the delay added on top of it, 0.0 - 1.0
+    pub jitter: f64,
+    /// Upper bound of a single delay
+    pub max_delay: Duration,
+    /// Give up once this much time has passed since the first attempt
+    pub max_elapsed: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_delay: Duration::from_secs(5),
+            multiplier: 2.0,
+            jitter: 0.2,
+            max_delay: Duration::from_secs(125),
+            max_elapsed: Duration::from_secs(180),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that never retries
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Set the total number of attempts
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set the delay before the first retry
+    pub fn initial_delay(mut self, initial_delay: Duration) -> Self {
+        self.initial_delay = initial_delay;
+        self
+    }
+
+    /// Set the backoff multiplier
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Set the jitter fraction
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set the upper bound of a single delay
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Set the total time allowed for all attempts
+    pub fn max_elapsed(mut self, max_elapsed: Duration) -> Self {
+        self.max_elapsed = max_elapsed;
+        self
+    }
+
+    /// Delay before retry number `retry`, starting at 0, without jitter
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        let factor = self.multiplier.max(1.0).powi(retry.min(i32::MAX as u32) as i32);
+        let delay = self.initial_delay.as_secs_f64() * factor;
+
+        if !delay.is_finite() || delay >= self.max_delay.as_secs_f64() {
+            self.max_delay
+        } else {
+            Duration::from_secs_f64(delay)
+        }
+    }
+
+    fn jittered(&self, delay: Duration) -> Duration {
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return delay;
+        }
+
+        let random = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+        delay.mul_f64(1.0 + jitter * random)
+    }
+
+    /// Send the request built by `request`, retrying as the policy allows
+    ///
+    /// The closure is called once per attempt since a `RequestBuilder` cannot be reused.
+    pub(crate) async fn send<F>(&self, client: &Client, request: F) -> Result<Response, error::Error>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let start = Instant::now();
+        let mut retry = 0;
+
+        loop {
+            let result = client.send(request()).await;
+            let attempts_left = retry + 1 < self.max_attempts;
+
+            let delay = match result {
+                Ok(ref res) if attempts_left && is_retryable_status(res.status()) => {
+                    retry_after(res).unwrap_or_else(|| self.jittered(self.backoff(retry)))
+                }
+                Err(ref err) if attempts_left && is_retryable_error(err) => {
+                    self.jittered(self.backoff(retry))
+                }
+                _ => return Ok(result?),
+            };
+
+            if start.elapsed() + delay > self.max_elapsed {
+                return Ok(result?);
+            }
+
+            tokio::time::sleep(delay).await;
+            retry += 1;
+        }
+    }
+}
+
+/// 429 and 5xx responses other than 501 Not Implemented are worth retrying
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || (status.is_server_error() && status != StatusCode::NOT_IMPLEMENTED)
+}
+
+pub(crate) fn is_retryable_error(err: &reqwest::Error) -> bool {
+    err.is_connect() || err.is_timeout() || err.is_request()
+}
+
+/// Parse the `Retry-After` header given in seconds
+pub(crate) fn retry_after(res: &Response) -> Option<Duration> {
+    parse_retry_after(res.headers().get(header::RETRY_AFTER)?.to_str().ok()?)
+}
+
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_grows_and_caps() {
+        let policy = RetryPolicy::default()
+            .initial_delay(Duration::from_secs(1))
+            .multiplier(2.0)
+            .max_delay(Duration::from_secs(5));
+
+        assert_eq!(policy.backoff(0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(1), Duration::from_secs(2));
+        assert_eq!(policy.backoff(2), Duration::from_secs(4));
+        assert_eq!(policy.backoff(3), Duration::from_secs(5));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn jitter_stays_in_bounds() {
+        let policy = RetryPolicy::default().jitter(0.5);
+        let delay = policy.jittered(Duration::from_secs(2));
+
+        assert!(delay >= Duration::from_secs(2));
+        assert!(delay <= Duration::from_secs(3));
+    }
+
+    #[test]
+    fn retryable_statuses() {
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_retryable_status(StatusCode::NOT_IMPLEMENTED));
+        assert!(!is_retryable_status(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable_status(StatusCode::UNAUTHORIZED));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn retry_after_seconds() {
+        assert_eq!(parse_retry_after("30"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after(" 2 "), Some(Duration::from_secs(2)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+}
@@ -0,0 +1,380 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::error::{self, ApiError};
+use crate::line_protocol::{self, ParseError};
+use crate::{Point, Value};
+
+/// Lines of a write that InfluxDB rejected
+///
+/// `RejectedLine::index` is the position of the point in the iterator given to
+/// `write_points`, also when string fields hold line breaks. Points that are not
+/// listed were written.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
+pub struct WriteError {
+    /// Error returned by InfluxDB
+    pub error: ApiError,
+    /// Lines the error message could be traced back to
+    pub rejected: Vec<RejectedLine>,
+    /// Number of points InfluxDB reported as dropped
+    ///
+    /// Points dropped for being outside the retention period are counted here
+    /// but not listed in `rejected`, the server does not say which ones they were.
+    pub dropped: usize,
+}
+
+/// A single rejected line of a write body
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
+pub struct RejectedLine {
+    /// Line number as InfluxDB counts it, one per point and starting at 1
+    pub line: usize,
+    /// Index of the point in the submitted points, starting at 0
+    pub index: usize,
+    /// Why the line was rejected
+    pub reason: RejectReason,
+    /// Message InfluxDB gave for this line
+    pub message: String,
+}
+
+/// Cause of a rejected line
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+pub enum RejectReason {
+    /// The line protocol could not be parsed
+    Parse,
+    /// A field has a different type than already stored for the measurement
+    FieldTypeConflict,
+    /// The timestamp is malformed or out of range
+    InvalidTimestamp,
+    /// The point is older than the retention period of the bucket
+    BeyondRetention,
+    /// Anything else
+    Other,
+}
+
+impl WriteError {
+    /// Indices of the rejected points, in the order of the body
+    pub fn rejected_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self.rejected.iter().map(|r| r.index).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl RejectReason {
+    fn classify(message: &str) -> Self {
+        let message = message.to_lowercase();
+
+        if message.contains("field type conflict") {
+            RejectReason::FieldTypeConflict
+        } else if message.contains("retention") {
+            RejectReason::BeyondRetention
+        } else if message.contains("timestamp") || message.contains("time outside range") {
+            RejectReason::InvalidTimestamp
+        } else if message.contains("unable to parse") || message.contains("invalid") {
+            RejectReason::Parse
+        } else {
+            RejectReason::Other
+        }
+    }
+}
+
+/// Turn the error of a rejected write into a `PartialWrite` error when it names lines of `body`
+pub(crate) fn attach(error: error::Error, body: &[u8]) -> error::Error {
+    let api_error = match error.inner {
+        error::ErrorKind::BadRequest(ref e) | error::ErrorKind::Unprocessable(ref e) => e,
+        _ => return error,
+    };
+
+    let body = match std::str::from_utf8(body) {
+        Ok(body) => body,
+        Err(_) => return error,
+    };
+
+    match parse(api_error, body) {
+        Some(write_error) => error::Error {
+            inner: error::ErrorKind::PartialWrite(Box::new(write_error)),
+        },
+        None => error,
+    }
+}
+
+/// Trace the error message back to points of the body
+///
+/// InfluxDB numbers lines by point, a string field holding line breaks does not
+/// start a new line. The body is split the same way, so `line N` is point N - 1.
+pub(crate) fn parse(error: &ApiError, body: &str) -> Option<WriteError> {
+    let records = line_protocol::records(body);
+
+    let mut message = error.message.clone();
+    if let Some(ref err) = error.err {
+        if !message.contains(err.as_str()) {
+            message.push('\n');
+            message.push_str(err);
+        }
+    }
+
+    let mut rejected = Vec::new();
+
+    let numbered = numbered_entries(&message);
+    if numbered.is_empty() {
+        rejected.extend(quoted_entries(&message, &records));
+        for entry in message.split('\n') {
+            rejected.extend(conflict_entry(entry, &records));
+        }
+    } else {
+        for (line, entry) in numbered {
+            if line == 0 || line > records.len() {
+                continue;
+            }
+            rejected.push(rejected_line(line - 1, entry));
+        }
+    }
+
+    let dropped = dropped_count(&message);
+
+    if rejected.is_empty() && dropped == 0 {
+        return None;
+    }
+
+    Some(WriteError {
+        error: error.clone(),
+        rejected,
+        dropped,
+    })
+}
+
+type Record<'a> = (&'a str, Result<Point<'a>, ParseError>);
+
+fn rejected_line(index: usize, entry: &str) -> RejectedLine {
+    RejectedLine {
+        line: index + 1,
+        index,
+        reason: RejectReason::classify(entry),
+        message: entry.to_string(),
+    }
+}
+
+/// `line N: message` entries, each running until the next entry or the end of its line
+fn numbered_entries(message: &str) -> Vec<(usize, &str)> {
+    let mut starts = Vec::new();
+    let mut search = 0;
+
+    while let Some(found) = message[search..].find("line ") {
+        let at = search + found;
+        let rest = &message[at + 5..];
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+
+        let preceded = message[..at]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric());
+
+        if digits > 0 && preceded && rest[digits..].starts_with(':') {
+            let line = rest[..digits].parse().unwrap_or(0);
+            starts.push((at, at + 5 + digits + 1, line));
+        }
+        search = at + 5;
+    }
+
+    let mut entries = Vec::new();
+    for (i, &(_, text_start, line)) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).map_or(message.len(), |next| next.0);
+        let text = &message[text_start..end];
+        let text = text.split('\n').next().unwrap_or_default();
+        entries.push((line, text.trim()));
+    }
+    entries
+}
+
+/// `unable to parse '<line>': reason` entries, located by the quoted point
+///
+/// The quoted text may span lines, so it is matched against whole points rather
+/// than split off at line breaks. InfluxDB capitalizes the message in places.
+fn quoted_entries(message: &str, records: &[Record]) -> Vec<RejectedLine> {
+    const PREFIX: &str = "nable to parse '";
+
+    let mut rejected = Vec::new();
+
+    for (at, _) in message.match_indices(PREFIX) {
+        let quoted = &message[at + PREFIX.len()..];
+
+        let found = records.iter().position(|(text, _)| {
+            quoted.starts_with(text) && quoted[text.len()..].starts_with("':")
+        });
+
+        if let Some(index) = found {
+            let after = at + PREFIX.len() + records[index].0.len();
+            let end = message[after..].find('\n').map_or(message.len(), |end| after + end);
+            let entry = &message[at.saturating_sub(1)..end];
+            rejected.push(rejected_line(index, entry.trim()));
+        }
+    }
+
+    rejected
+}
+
+/// Field type conflict entry, located by measurement, field and value type
+fn conflict_entry(entry: &str, records: &[Record]) -> Vec<RejectedLine> {
+    let entry = entry.trim();
+    let (measurement, field, kind) = match field_conflict(entry) {
+        Some(conflict) => conflict,
+        None => return Vec::new(),
+    };
+
+    records
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, point))| match point {
+            Ok(point) => point.measurement == measurement
+                && point.fields.get(field).map(value_type) == Some(kind),
+            Err(_) => false,
+        })
+        .map(|(index, _)| rejected_line(index, entry))
+        .collect()
+}
+
+/// Measurement, field and input type of `input field "f" on measurement "m" is type t`
+fn field_conflict(entry: &str) -> Option<(&str, &str, &str)> {
+    let field = between(entry, "input field \"", "\"")?;
+    let measurement = between(entry, "on measurement \"", "\"")?;
+    let kind = between(entry, "is type ", ",")
+        .or_else(|| between(entry, "is type ", " "))?;
+    Some((measurement, field, kind))
+}
+
+fn between<'a>(text: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let from = text.find(start)? + start.len();
+    let to = text[from..].find(end)? + from;
+    Some(&text[from..to])
+}
+
+/// Type name InfluxDB uses for a field value
+fn value_type(value: &Value) -> &'static str {
+    match value {
+        Value::String(_) => "string",
+        Value::Integer(_) => "integer",
+        Value::UInteger(_) => "unsigned",
+        Value::Float(_) => "float",
+        Value::Boolean(_) => "boolean",
+    }
+}
+
+/// Sum of the `dropped=N` counts in the message
+fn dropped_count(message: &str) -> usize {
+    message
+        .match_indices("dropped=")
+        .filter_map(|(at, key)| {
+            let rest = &message[at + key.len()..];
+            let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+            rest[..digits].parse::<usize>().ok()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = "cpu,host=a value=1i 1\ncpu,host=b value=2.5 2\ncpu,host=c value=3i 3\nmem free=bad 4\n";
+
+    fn api_error(status: u16, message: &str) -> ApiError {
+        ApiError::parse(status, "POST", "/api/v2/write", &serde_json::json!({
+            "code": "invalid",
+            "message": message,
+        }).to_string())
+    }
+
+    #[test]
+    fn numbered_lines() {
+        let error = api_error(400, "failed to parse line protocol: errors encountered on line(s):\nline 4: unable to parse 'mem free=bad 4': invalid boolean\nline 2: unable to parse 'cpu,host=b value=2.5 2x': bad timestamp");
+
+        let write_error = parse(&error, BODY).unwrap();
+        assert_eq!(write_error.rejected.len(), 2);
+
+        assert_eq!(write_error.rejected[0].line, 4);
+        assert_eq!(write_error.rejected[0].index, 3);
+        assert_eq!(write_error.rejected[0].reason, RejectReason::Parse);
+        assert_eq!(write_error.rejected[0].message, "unable to parse 'mem free=bad 4': invalid boolean");
+
+        assert_eq!(write_error.rejected[1].reason, RejectReason::InvalidTimestamp);
+        assert_eq!(write_error.rejected_indices(), vec![1, 3]);
+    }
+
+    #[test]
+    fn quoted_lines() {
+        let error = api_error(400, "partial write: unable to parse 'mem free=bad 4': invalid boolean");
+
+        let write_error = parse(&error, BODY).unwrap();
+        assert_eq!(write_error.rejected_indices(), vec![3]);
+        assert_eq!(write_error.rejected[0].line, 4);
+    }
+
+    #[test]
+    fn field_type_conflict() {
+        let error = api_error(422, "failure writing points to database: partial write: field type conflict: input field \"value\" on measurement \"cpu\" is type integer, already exists as type float dropped=2");
+
+        let write_error = parse(&error, BODY).unwrap();
+        assert_eq!(write_error.rejected_indices(), vec![0, 2]);
+        assert_eq!(write_error.rejected[0].reason, RejectReason::FieldTypeConflict);
+        assert_eq!(write_error.dropped, 2);
+    }
+
+    #[test]
+    fn retention_is_counted() {
+        let error = api_error(422, "partial write: points beyond retention policy dropped=3");
+
+        let write_error = parse(&error, BODY).unwrap();
+        assert!(write_error.rejected.is_empty());
+        assert_eq!(write_error.dropped, 3);
+    }
+
+    #[test]
+    fn unrelated_errors_are_kept() {
+        let error = error::Error::from_status(api_error(400, "invalid precision"), None);
+        let error = attach(error, BODY.as_bytes());
+        assert!(matches!(error.inner, error::ErrorKind::BadRequest(_)));
+
+        let error = error::Error::from_status(api_error(400, "partial write: unable to parse 'mem free=bad 4': invalid boolean"), None);
+        let error = attach(error, BODY.as_bytes());
+        assert_eq!(error.write_error().unwrap().rejected_indices(), vec![3]);
+        assert_eq!(error.status(), Some(400));
+    }
+
+    #[test]
+    fn multi_line_strings() {
+        let body = "log msg=\"first\nsecond\nthird\" 1\ncpu value=2.5 2\ncpu value=3i 3\nmem free=bad 4\n";
+
+        let error = api_error(422, "field type conflict: input field \"value\" on measurement \"cpu\" is type integer, already exists as type float dropped=1");
+        let write_error = parse(&error, body).unwrap();
+        assert_eq!(write_error.rejected_indices(), vec![2]);
+        assert_eq!(write_error.rejected[0].line, 3);
+
+        let error = api_error(400, "partial write: unable to parse 'mem free=bad 4': invalid boolean");
+        assert_eq!(parse(&error, body).unwrap().rejected_indices(), vec![3]);
+
+        let error = api_error(400, "errors encountered on line(s):\nline 4: unable to parse 'mem free=bad 4': invalid boolean");
+        assert_eq!(parse(&error, body).unwrap().rejected_indices(), vec![3]);
+
+        // The quoted point itself spans lines
+        let body = "log msg=\"a\nb\",n=x 1\ncpu value=1 2\n";
+        let error = api_error(400, "partial write: unable to parse 'log msg=\"a\nb\",n=x 1': invalid boolean");
+        let write_error = parse(&error, body).unwrap();
+        assert_eq!(write_error.rejected_indices(), vec![0]);
+        assert_eq!(write_error.rejected[0].message, "unable to parse 'log msg=\"a\nb\",n=x 1': invalid boolean");
+    }
+
+    #[test]
+    fn escaped_measurements() {
+        let body = "my\\ cpu,host=a value=1i\nmy\\ cpu,host=b text=\"a, b\",value=2i\n";
+        let error = api_error(422, "field type conflict: input field \"value\" on measurement \"my cpu\" is type integer, already exists as type float dropped=2");
+
+        assert_eq!(parse(&error, body).unwrap().rejected_indices(), vec![0, 1]);
+    }
+}
@@ -1,8 +1,8 @@
-use influxdb_rs::Client;
+use influxdb_rs::{Client, SharedToken};
 use influxdb_rs::data_model::user::Status;
-use influxdb_rs::data_model::authorization::AuthPermissions;
-use influxdb_rs::data_model::authorization::AuthResource;
+use influxdb_rs::data_model::authorization::Permission;
 use influxdb_rs::data_model::authorization::AuthResourceType;
+use influxdb_rs::data_model::authorization::{AuthorizationFilter, UpdateAuthorizationRequest};
 use url::Url;
 use chrono::Utc;
 
@@ -34,20 +34,13 @@ async fn reduced_perms() {
     assert_ne!(client.org_id, "");
 
     //Create Read Permissions
-    let permissions = vec![AuthPermissions {
-        action: "read".to_string(),
-        resource: AuthResource {
-            r#type: AuthResourceType::Bucket.to_string(),
-            org_id: None,
-            bucket_id: None,
-        },
-    }];
+    let permissions = vec![Permission::read(AuthResourceType::Bucket)];
 
     // Create a new authorization
     let create_auth = client.create_authorization(None, &client.org_id, permissions, Status::Active, "read bucket").await;
 
     // Assert that it succeeded
-    assert!(create_auth.is_ok(), "CREATE AUTH DIDNT WORK: {} ORG ID: {} AuthType: {}", create_auth.unwrap_err(), client.org_id, AuthResourceType::Bucket.to_string());
+    assert!(create_auth.is_ok(), "CREATE AUTH DIDNT WORK: {} ORG ID: {} AuthType: {}", create_auth.unwrap_err(), client.org_id, AuthResourceType::Bucket);
 
     //Get Auth Response
     let auth = create_auth.unwrap();
@@ -68,7 +61,7 @@ async fn reduced_perms() {
     // NOTE: convert time from timstamp_nanos() due to to_rfc3339() doesn't convert nicely with GOLANG
     let flux_query = format!("from(bucket: \"test_bucket\") 
     |> range(start: time(v: {:?}))
-    |> yield()", now.timestamp_nanos());
+    |> yield()", now.timestamp_nanos_opt().unwrap());
 
     let query = influxdb_rs::data_model::query::ReadQuery{
         r#extern: None,
@@ -76,6 +69,7 @@ async fn reduced_perms() {
         r#type: None,
         dialect: None,
         now: None,
+        params: None,
 
     };
 
@@ -89,3 +83,131 @@ async fn reduced_perms() {
 
 }
 
+
+
+#[tokio::test]
+async fn authorization_lifecycle() {
+
+    let client = Client::new(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "0123456789").await.unwrap();
+
+    let permissions = vec![Permission::read(AuthResourceType::Bucket)];
+
+    let auth = client.create_authorization(None, &client.org_id, permissions, Status::Active, "lifecycle").await.unwrap();
+
+    // Look the token up by its value
+    let found = client.list_authorizations(&AuthorizationFilter::default().token(auth.token.clone())).await;
+    assert!(found.is_ok(), "LIST AUTH DIDNT WORK: {}", found.unwrap_err());
+    assert!(found.unwrap().iter().any(|a| a.id == auth.id));
+
+    let by_org = client.list_authorizations(&AuthorizationFilter::default().org_id(client.org_id.clone())).await.unwrap();
+    assert!(by_org.iter().any(|a| a.id == auth.id));
+
+    let update = UpdateAuthorizationRequest::default().status(Status::Inactive).description("rotated");
+    let updated = client.update_authorization(&auth.id, update).await;
+    assert!(updated.is_ok(), "UPDATE AUTH DIDNT WORK: {}", updated.unwrap_err());
+
+    let fetched = client.get_authorization(&auth.id).await.unwrap();
+    assert_eq!(fetched.status, Status::Inactive);
+    assert_eq!(fetched.description, "rotated");
+
+    let deleted = client.delete_authorization(&auth.id).await;
+    assert!(deleted.is_ok(), "DELETE AUTH DIDNT WORK: {}", deleted.unwrap_err());
+
+    assert!(client.get_authorization(&auth.id).await.is_err());
+}
+
+
+#[tokio::test]
+async fn bucket_scoped_token() {
+
+    let client = Client::new(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "0123456789").await.unwrap();
+
+    let bucket_id = client.get_bucket_id("test_bucket").await.unwrap();
+    let permissions = Permission::read_write_bucket(&client.org_id, &bucket_id);
+
+    let auth = client.create_authorization(None, &client.org_id, permissions, Status::Active, "test_bucket only").await;
+    assert!(auth.is_ok(), "CREATE AUTH DIDNT WORK: {}", auth.unwrap_err());
+    let auth = auth.unwrap();
+
+    assert_eq!(auth.permissions.len(), 2);
+    assert!(auth.permissions.iter().all(|p| p.resource.id.as_deref() == Some(bucket_id.as_str())));
+
+    let scoped = Client::new_without_org_id(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", &auth.token).await.unwrap();
+    let point = influxdb_rs::Point::new("scoped_token").add_field("value", 1);
+    assert!(scoped.write_point(point, None, None).await.is_ok());
+
+    assert!(client.delete_authorization(&auth.id).await.is_ok());
+}
+
+
+#[tokio::test]
+async fn session_auth() {
+
+    let client = Client::new_with_session(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "admin", "password123!").await;
+    assert!(client.is_ok(), "SESSION CLIENT DIDNT WORK: {}", client.unwrap_err());
+    let client = client.unwrap();
+
+    assert_ne!(client.org_id, "");
+
+    let me = client.get_me().await;
+    assert!(me.is_ok(), "GET ME DIDNT WORK: {}", me.unwrap_err());
+    assert_eq!(me.unwrap().name, "admin");
+
+    let point = influxdb_rs::Point::new("session_auth").add_field("value", 1);
+    assert!(client.write_point(point, None, None).await.is_ok());
+
+    let signout = client.signout().await;
+    assert!(signout.is_ok(), "SIGNOUT DIDNT WORK: {}", signout.unwrap_err());
+
+    // Signed out sessions are not renewed
+    assert!(client.get_me().await.is_err());
+
+    let wrong = Client::new_with_session(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "admin", "wrong").await;
+    assert!(wrong.is_err());
+}
+
+
+#[tokio::test]
+async fn rotate_token() {
+
+    let token = SharedToken::new("not-a-token");
+
+    let client = Client::new_without_org_id(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "0123456789").await.unwrap()
+        .set_credentials(token.clone());
+
+    assert!(client.list_orgs().await.is_err());
+
+    // Swapped without rebuilding the client
+    token.set("0123456789");
+    let orgs = client.list_orgs().await;
+    assert!(orgs.is_ok(), "ROTATED TOKEN DIDNT WORK: {}", orgs.unwrap_err());
+
+    let rotated = client.clone().set_jwt_token("not-a-token");
+    assert!(rotated.list_orgs().await.is_err());
+}
+
+
+#[tokio::test]
+async fn builder_client() {
+
+    let client = Client::builder(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org")
+        .token("0123456789")
+        .connect_timeout(std::time::Duration::from_secs(5))
+        .timeout(std::time::Duration::from_secs(30))
+        .no_proxy()
+        .build()
+        .await;
+    assert!(client.is_ok(), "BUILDER DIDNT WORK: {}", client.unwrap_err());
+    assert_ne!(client.unwrap().org_id, "");
+
+    // Lazy clients resolve the org ID when a call needs it
+    let lazy = Client::builder(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org")
+        .token("0123456789")
+        .lazy_org_id()
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(lazy.org_id, "");
+    assert!(lazy.create_database("builder_lazy").await.is_ok());
+    assert!(lazy.drop_database("builder_lazy").await.is_ok());
+}
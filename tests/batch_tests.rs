@@ -0,0 +1,85 @@
+use influxdb_rs::{BatchOptions, Client, Point, Precision};
+use url::Url;
+use chrono::prelude::*;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[tokio::test]
+async fn batch_writer_flushes_and_closes() {
+    // Create client with a parsed url, bucket, org, and jwt token
+    let client = Client::new(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "0123456789").await.unwrap();
+
+    let now = Utc::now();
+
+    let failures = Arc::new(Mutex::new(Vec::new()));
+    let errors = failures.clone();
+
+    let options = BatchOptions::default()
+        .max_points(10)
+        .flush_interval(Duration::from_millis(100))
+        .precision(Precision::Nanoseconds);
+
+    let writer = client.batch_writer(options, move |err| errors.lock().unwrap().push(err));
+
+    for i in 0..25 {
+        let point = Point::new("batch_test")
+            .add_field("value", i)
+            .add_timestamp(now.timestamp_nanos_opt().unwrap() + i as i64);
+
+        let result = writer.write(point).await;
+        assert!(result.is_ok());
+    }
+
+    let flush = writer.flush().await;
+    assert!(flush.is_ok());
+
+    let close = writer.close().await;
+    assert!(close.is_ok());
+
+    assert!(failures.lock().unwrap().is_empty(), "BATCH WRITE FAILED: {:?}", failures.lock().unwrap());
+
+    let later = Utc::now().to_rfc3339().to_string();
+
+    let drop = client.drop_measurement("batch_test", &now.to_rfc3339(), &later).await;
+
+    assert!(drop.is_ok());
+}
+
+#[tokio::test]
+async fn batch_writer_without_interval() {
+    // Create client with a parsed url, bucket, org, and jwt token
+    let client = Client::new(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "0123456789").await.unwrap();
+
+    let now = Utc::now();
+
+    let failures = Arc::new(Mutex::new(Vec::new()));
+    let errors = failures.clone();
+
+    // Zero disables interval flushing, small batches split on max_bytes
+    let options = BatchOptions::default()
+        .max_bytes(64)
+        .flush_interval(Duration::ZERO)
+        .precision(Precision::Nanoseconds);
+
+    let writer = client.batch_writer(options, move |err| errors.lock().unwrap().push(err));
+
+    for i in 0..5 {
+        let point = Point::new("batch_zero_interval")
+            .add_field("value", i)
+            .add_timestamp(now.timestamp_nanos_opt().unwrap() + i as i64);
+
+        let result = writer.write(point).await;
+        assert!(result.is_ok());
+    }
+
+    let close = writer.close().await;
+    assert!(close.is_ok());
+
+    assert!(failures.lock().unwrap().is_empty(), "BATCH WRITE FAILED: {:?}", failures.lock().unwrap());
+
+    let later = Utc::now().to_rfc3339().to_string();
+
+    let drop = client.drop_measurement("batch_zero_interval", &now.to_rfc3339(), &later).await;
+
+    assert!(drop.is_ok());
+}
@@ -0,0 +1,49 @@
+use influxdb_rs::Client;
+use influxdb_rs::data_model::authorization::AuthorizationFilter;
+use influxdb_rs::data_model::bucket::{BucketFilter, CreateBucketRequest};
+use influxdb_rs::data_model::links::PageOptions;
+use url::Url;
+use futures::prelude::*;
+
+#[tokio::test]
+async fn paginated_buckets() {
+    // Create client with a parsed url, bucket, org, and jwt token
+    let client = Client::new(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "0123456789").await.unwrap();
+
+    let names: Vec<String> = (0..3).map(|i| format!("page_test_{}", i)).collect();
+
+    for name in &names {
+        let created = client.create_bucket(CreateBucketRequest::new(client.org_id.clone(), name.clone())).await;
+        assert!(created.is_ok(), "CREATE BUCKET DIDNT WORK: {}", created.unwrap_err());
+    }
+
+    // One bucket per page forces the stream to follow links.next
+    let filter = BucketFilter::default().org_id(client.org_id.clone());
+    let buckets: Result<Vec<_>, _> = client.buckets_stream(&filter, &PageOptions::default().limit(1)).try_collect().await;
+    assert!(buckets.is_ok(), "BUCKET STREAM DIDNT WORK: {}", buckets.unwrap_err());
+
+    let buckets = buckets.unwrap();
+    assert!(names.iter().all(|name| buckets.iter().any(|b| &b.name == name)));
+
+    let id = client.get_bucket_id("page_test_1").await;
+    assert!(id.is_ok(), "GET BUCKET ID DIDNT WORK: {}", id.unwrap_err());
+
+    let missing = client.get_bucket_id("page_test_missing").await;
+    assert!(missing.is_err());
+
+    let orgs: Result<Vec<_>, _> = client.orgs_stream(&PageOptions::default().limit(1)).try_collect().await;
+    assert!(orgs.unwrap().iter().any(|o| o.name == "test_org"));
+
+    let authorizations: Result<Vec<_>, _> = client.authorizations_stream(&AuthorizationFilter::default(), &PageOptions::default()).try_collect().await;
+    assert!(authorizations.is_ok(), "AUTHORIZATION STREAM DIDNT WORK: {}", authorizations.unwrap_err());
+
+    let labels: Result<Vec<_>, _> = client.labels_stream(&PageOptions::default()).try_collect().await;
+    assert!(labels.is_ok(), "LABEL STREAM DIDNT WORK: {}", labels.unwrap_err());
+
+    let tasks: Result<Vec<_>, _> = client.tasks_stream(&PageOptions::default().limit(1)).try_collect().await;
+    assert!(tasks.is_ok(), "TASK STREAM DIDNT WORK: {}", tasks.unwrap_err());
+
+    for name in &names {
+        assert!(client.drop_database(name).await.is_ok());
+    }
+}
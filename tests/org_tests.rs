@@ -0,0 +1,48 @@
+use influxdb_rs::Client;
+use influxdb_rs::data_model::org::{CreateOrgRequest, Role, UpdateOrgRequest};
+use influxdb_rs::data_model::user::Status;
+use url::Url;
+
+#[tokio::test]
+async fn manage_org() {
+    // Create client with a parsed url, bucket, org, and jwt token
+    let client = Client::new(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "0123456789").await.unwrap();
+
+    let org = client.create_org(CreateOrgRequest::new("org_test").description("created by org_tests")).await;
+    assert!(org.is_ok(), "CREATE ORG DIDNT WORK: {}", org.unwrap_err());
+    let org = org.unwrap();
+
+    let updated = client.update_org(&org.id, UpdateOrgRequest::default().name("org_test_renamed")).await;
+    assert!(updated.is_ok(), "UPDATE ORG DIDNT WORK: {}", updated.unwrap_err());
+    assert_eq!(updated.unwrap().name, "org_test_renamed");
+
+    let fetched = client.get_org(&org.id).await;
+    assert!(fetched.is_ok(), "GET ORG DIDNT WORK: {}", fetched.unwrap_err());
+    assert_eq!(fetched.unwrap().description.as_deref(), Some("created by org_tests"));
+
+    let orgs = client.list_orgs().await;
+    assert!(orgs.is_ok(), "LIST ORGS DIDNT WORK: {}", orgs.unwrap_err());
+    assert!(orgs.unwrap().iter().any(|o| o.id == org.id));
+
+    let user = client.create_new_user("org_test_user", Status::Active).await.unwrap();
+
+    let member = client.add_org_member(&org.id, &user.id).await;
+    assert!(member.is_ok(), "ADD MEMBER DIDNT WORK: {}", member.unwrap_err());
+    assert_eq!(member.unwrap().role, Role::Member);
+
+    let members = client.list_org_members(&org.id).await.unwrap();
+    assert!(members.iter().any(|m| m.id == user.id));
+
+    let owner = client.add_org_owner(&org.id, &user.id).await;
+    assert!(owner.is_ok(), "ADD OWNER DIDNT WORK: {}", owner.unwrap_err());
+
+    let owners = client.list_org_owners(&org.id).await.unwrap();
+    assert!(owners.iter().any(|o| o.id == user.id && o.role == Role::Owner));
+
+    assert!(client.remove_org_owner(&org.id, &user.id).await.is_ok());
+    assert!(client.remove_org_member(&org.id, &user.id).await.is_ok());
+    assert!(client.delete_user(&user.id).await.is_ok());
+
+    let deleted = client.delete_org(&org.id).await;
+    assert!(deleted.is_ok(), "DELETE ORG DIDNT WORK: {}", deleted.unwrap_err());
+}
@@ -0,0 +1,152 @@
+use influxdb_rs::{Client, Compression, Point, Precision};
+use influxdb_rs::data_model::flux::FluxValue;
+use influxdb_rs::data_model::query::ReadQuery;
+use url::Url;
+use chrono::prelude::*;
+use futures::prelude::*;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct Row {
+    #[serde(rename = "_time")]
+    time: DateTime<Utc>,
+    #[serde(rename = "_value")]
+    value: i64,
+    #[serde(rename = "_field")]
+    field: String,
+    host: String,
+}
+
+fn measurement_query(measurement: &str, start: DateTime<Utc>) -> ReadQuery {
+    // NOTE: convert time from timstamp_nanos() due to to_rfc3339() doesn't convert nicely with GOLANG
+    let flux_query = format!("from(bucket: \"test_bucket\")
+    |> range(start: time(v: {:?}))
+    |> filter(fn: (r) => r._measurement == \"{}\")
+    |> yield()", start.timestamp_nanos_opt().unwrap(), measurement);
+
+    ReadQuery {
+        query: flux_query,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn query_tables_and_stream() {
+    // Create client with a parsed url, bucket, org, and jwt token
+    let client = Client::new(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "0123456789").await.unwrap();
+
+    let now = Utc::now();
+
+    let points: Vec<Point> = (0..10)
+        .map(|i| Point::new("query_test")
+            .add_tag("host", if i % 2 == 0 { "even" } else { "odd" })
+            .add_field("value", i)
+            .add_timestamp(now.timestamp_nanos_opt().unwrap() + i as i64))
+        .collect();
+
+    let write = client.write_points(&points, Some(Precision::Nanoseconds), None).await;
+    assert!(write.is_ok(), "WRITE DIDNT WORK: {}", write.unwrap_err());
+
+    let tables = client.query_tables(measurement_query("query_test", now)).await;
+    assert!(tables.is_ok(), "QUERY TABLES DIDNT WORK: {}", tables.unwrap_err());
+
+    // One table per host tag
+    let tables = tables.unwrap();
+    assert_eq!(tables.len(), 2);
+    assert_eq!(tables.iter().map(|t| t.records.len()).sum::<usize>(), 10);
+    assert!(matches!(tables[0].records[0].value(), Some(FluxValue::Long(_))));
+
+    let stream = client.query_stream(measurement_query("query_test", now)).await;
+    assert!(stream.is_ok(), "QUERY STREAM DIDNT WORK");
+
+    let records: Vec<_> = stream.unwrap().try_collect().await.unwrap();
+    assert_eq!(records.len(), 10);
+    assert_eq!(records.iter().filter(|r| r.table == 1).count(), 5);
+
+    let rows = client.query_as::<Row>(measurement_query("query_test", now)).await;
+    assert!(rows.is_ok(), "QUERY AS DIDNT WORK: {}", rows.unwrap_err());
+
+    let rows = rows.unwrap();
+    assert_eq!(rows.len(), 10);
+    assert!(rows.iter().all(|r| r.time >= now && r.field == "value"));
+    assert_eq!(rows.iter().filter(|r| r.host == "even").map(|r| r.value).sum::<i64>(), 20);
+
+    let later = Utc::now().to_rfc3339().to_string();
+
+    let drop = client.drop_measurement("query_test", &now.to_rfc3339(), &later).await;
+
+    assert!(drop.is_ok());
+}
+
+#[tokio::test]
+async fn analyze_and_suggest() {
+    // Create client with a parsed url, bucket, org, and jwt token
+    let client = Client::new(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "0123456789").await.unwrap();
+
+    let valid = client.analyze_query(&ReadQuery::new("from(bucket: \"test_bucket\") |> range(start: -1h)")).await;
+    assert!(valid.is_ok(), "ANALYZE DIDNT WORK: {}", valid.unwrap_err());
+    assert!(valid.unwrap().errors.is_empty());
+
+    // Missing closing parenthesis
+    let invalid = client.analyze_query(&ReadQuery::new("from(bucket: \"test_bucket\" |> range(start: -1h)")).await;
+    assert!(invalid.is_ok(), "ANALYZE DIDNT WORK: {}", invalid.unwrap_err());
+    assert!(!invalid.unwrap().errors.is_empty());
+
+    let ast = client.query_ast("from(bucket: \"test_bucket\")").await;
+    assert!(ast.is_ok(), "AST DIDNT WORK: {}", ast.unwrap_err());
+    assert!(ast.unwrap().ast.is_some());
+
+    let suggestions = client.query_suggestions().await;
+    assert!(suggestions.is_ok(), "SUGGESTIONS DIDNT WORK: {}", suggestions.unwrap_err());
+    assert!(suggestions.unwrap().iter().any(|s| s.name == "range"));
+
+    let range = client.query_suggestion("range").await;
+    assert!(range.is_ok(), "SUGGESTION DIDNT WORK: {}", range.unwrap_err());
+    assert!(range.unwrap().params.contains_key("start"));
+}
+
+#[tokio::test]
+async fn influxql_show_databases() {
+    // Create client with a parsed url, bucket, org, and jwt token
+    let client = Client::new(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "0123456789").await.unwrap();
+
+    let result = client.query_influxql("test_bucket", None, "SHOW DATABASES", Some(Precision::Seconds)).await;
+    assert!(result.is_ok(), "INFLUXQL DIDNT WORK: {}", result.unwrap_err());
+    assert_eq!(result.unwrap().results.len(), 1);
+
+    let chunked = client.query_influxql_chunked("test_bucket", None, "SHOW DATABASES", None, 1).await;
+    assert!(chunked.is_ok(), "CHUNKED INFLUXQL DIDNT WORK: {}", chunked.unwrap_err());
+}
+
+#[tokio::test]
+async fn gzip_write_and_query() {
+    // Gzip write bodies and accept gzipped query responses
+    let builder = Client::builder(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org")
+        .token("0123456789")
+        .compression(Compression::Gzip);
+
+    #[cfg(feature = "gzip")]
+    let builder = builder.accept_gzip(true);
+
+    let client = builder.build().await.unwrap();
+
+    let now = Utc::now();
+
+    let points: Vec<Point> = (0..100)
+        .map(|i| Point::new("gzip_test")
+            .add_tag("host", "edge")
+            .add_field("value", i)
+            .add_timestamp(now.timestamp_nanos_opt().unwrap() + i as i64))
+        .collect();
+
+    let write = client.write_points(&points, Some(Precision::Nanoseconds), None).await;
+    assert!(write.is_ok(), "GZIP WRITE DIDNT WORK: {}", write.unwrap_err());
+
+    let tables = client.query_tables(measurement_query("gzip_test", now)).await;
+    assert!(tables.is_ok(), "GZIP QUERY DIDNT WORK: {}", tables.unwrap_err());
+    assert_eq!(tables.unwrap().iter().map(|t| t.records.len()).sum::<usize>(), 100);
+
+    let later = Utc::now().to_rfc3339().to_string();
+    let drop = client.drop_measurement("gzip_test", &now.to_rfc3339(), &later).await;
+    assert!(drop.is_ok());
+}
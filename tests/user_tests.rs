@@ -1,7 +1,6 @@
 use influxdb_rs::Client;
-use influxdb_rs::data_model::user::Status;
-use influxdb_rs::data_model::authorization::AuthPermissions;
-use influxdb_rs::data_model::authorization::AuthResource;
+use influxdb_rs::data_model::user::{Status, UpdateUserRequest, UserFilter};
+use influxdb_rs::data_model::authorization::Permission;
 use influxdb_rs::data_model::authorization::AuthResourceType;
 use url::Url;
 
@@ -21,7 +20,7 @@ async fn users() {
         assert_ne!(client.org_id, "");
     
         // List Users
-        let list_users = client.list_users().await;
+        let list_users = client.list_users(&UserFilter::default()).await;
         // Assert that it succeeded
         assert!(list_users.is_ok(), "LIST USERS DIDNT WORK: {}", list_users.unwrap_err());
     
@@ -44,17 +43,39 @@ async fn users() {
         assert!(create_user.is_ok(), "CREATE USER DIDNT WORK: {}", create_user.unwrap_err());
     
         // Get Create User Response
-        let user = create_user.unwrap();
+        let _user = create_user.unwrap();
     
         //Create Read Permissions
-        let permissions = vec![AuthPermissions {
-            action: "read".to_string(),
-            resource: AuthResource {
-                r#type: AuthResourceType::Bucket.to_string(),
-                org_id: None,
-                bucket_id: None,
-            },
-        }];
+        let _permissions = [Permission::read(AuthResourceType::Bucket)];
 
 }
 
+
+#[tokio::test]
+async fn manage_user() {
+
+    let client = Client::new(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "0123456789").await.unwrap();
+
+    let me = client.get_me().await;
+    assert!(me.is_ok(), "GET ME DIDNT WORK: {}", me.unwrap_err());
+    assert_eq!(me.unwrap().status, Status::Active);
+
+    let user = client.create_new_user("manage_user", Status::Active).await.unwrap();
+
+    let found = client.list_users(&UserFilter::default().name("manage_user")).await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, user.id);
+
+    let update = UpdateUserRequest::default().name("manage_user_renamed").status(Status::Inactive);
+    let updated = client.update_user(&user.id, update).await;
+    assert!(updated.is_ok(), "UPDATE USER DIDNT WORK: {}", updated.unwrap_err());
+
+    let fetched = client.get_user(&user.id).await.unwrap();
+    assert_eq!(fetched.name, "manage_user_renamed");
+    assert_eq!(fetched.status, Status::Inactive);
+
+    let password = client.set_user_password(&user.id, "a-long-enough-password").await;
+    assert!(password.is_ok(), "SET PASSWORD DIDNT WORK: {}", password.unwrap_err());
+
+    assert!(client.delete_user(&user.id).await.is_ok());
+}
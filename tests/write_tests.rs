@@ -1,6 +1,9 @@
-use influxdb_rs::{point, points, Client, Point, Points, Precision};
+use influxdb_rs::{point, points, Client, Point, Points, Precision, RejectReason, WriteMode};
+use influxdb_rs::error::ErrorKind;
+use influxdb_rs::data_model::bucket::{BucketFilter, CreateBucketRequest, UpdateBucketRequest};
 use url::Url;
 use chrono::prelude::*;
+use std::time::Duration;
 
 #[tokio::test]
 async fn create_and_delete_database() {
@@ -13,13 +16,13 @@ async fn create_and_delete_database() {
     if create.is_ok() {
         let drop = client.drop_database("temporary").await;
 
-        assert_eq!(drop.is_ok(), true);
+        assert!(drop.is_ok());
 
     } else {
         // Delete bucket after verifying that one already exists
         let drop = client.drop_database("temporary").await;
 
-        assert_eq!(drop.is_ok(), true);
+        assert!(drop.is_ok());
     }
 
 }
@@ -42,7 +45,7 @@ async fn create_and_delete_measurement() {
 
     // No need to check to see whether the fieds and timestamp are available 
     // variable drop verifies that for us
-    assert_eq!(result.is_ok(), true);
+    assert!(result.is_ok());
 
     let later = Utc::now().to_rfc3339().to_string();
 
@@ -50,7 +53,7 @@ async fn create_and_delete_measurement() {
     // No Error means the value was present
     let drop = client.drop_measurement("temporary", &now.to_rfc3339(), &later).await;
 
-    assert_eq!(drop.is_ok(), true);
+    assert!(drop.is_ok());
 
 }
 
@@ -75,17 +78,17 @@ async fn use_points() {
 
     let write_points = client.write_points(points, Some(Precision::Seconds), None).await;
 
-    assert_eq!(write_points.is_ok(), true);
+    assert!(write_points.is_ok());
 
     let later = Utc::now().to_rfc3339().to_string();
 
     let drop1 = client.drop_measurement("test1", &now.to_rfc3339(), &later).await;
 
-    assert_eq!(drop1.is_ok(), true);
+    assert!(drop1.is_ok());
 
     let drop2 = client.drop_measurement("test2", &now.to_rfc3339(), &later).await;
 
-    assert_eq!(drop2.is_ok(), true);
+    assert!(drop2.is_ok());
 }
 
 #[tokio::test]
@@ -111,7 +114,7 @@ async fn query_with_macros() {
     let flux_query = format!("from(bucket: \"test_bucket\") 
     |> range(start: time(v: {:?}))
     |> filter(fn: (r) => r._measurement == \"test4\")
-    |> yield()", now.timestamp_nanos());
+    |> yield()", now.timestamp_nanos_opt().unwrap());
 
     let query = influxdb_rs::data_model::query::ReadQuery{
         r#extern: None,
@@ -119,15 +122,111 @@ async fn query_with_macros() {
         r#type: None,
         dialect: None,
         now: None,
+        params: None,
 
     };
 
     let result = client.query(Some(query)).await;
 
 
-    assert_eq!(result.is_ok(), true);
+    assert!(result.is_ok());
 
     let drop = client.drop_measurement("test4", &now.to_rfc3339(), &later.to_rfc3339()).await;
 
-    assert_eq!(drop.is_ok(), true);
-}
\ No newline at end of file
+    assert!(drop.is_ok());
+}
+
+#[tokio::test]
+async fn write_v1_endpoint() {
+    // Create client with a parsed url, bucket, org, and jwt token
+    let client = Client::new(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "0123456789").await.unwrap()
+        .set_write_mode(WriteMode::V1);
+
+    let now = Utc::now();
+
+    let point = Point::new("v1_write")
+        .add_tag("host", "server01")
+        .add_field("value", 1.5)
+        .add_timestamp(now.timestamp_millis());
+
+    let result = client.write_point(point, Some(Precision::Milliseconds), None).await;
+
+    assert!(result.is_ok(), "V1 WRITE DIDNT WORK: {}", result.unwrap_err());
+
+    let later = Utc::now().to_rfc3339().to_string();
+
+    let drop = client.drop_measurement("v1_write", &now.to_rfc3339(), &later).await;
+
+    assert!(drop.is_ok());
+}
+
+#[tokio::test]
+async fn manage_bucket() {
+    // Create client with a parsed url, bucket, org, and jwt token
+    let client = Client::new(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "0123456789").await.unwrap();
+
+    // Remove leftovers of a previous run
+    let _ = client.drop_database("managed_bucket").await;
+    let _ = client.drop_database("managed_bucket_renamed").await;
+
+    let request = CreateBucketRequest::new(client.org_id.as_str(), "managed_bucket")
+        .description("created by manage_bucket")
+        .retention(Duration::from_secs(3600));
+
+    let created = client.create_bucket(request).await;
+    assert!(created.is_ok(), "CREATE BUCKET DIDNT WORK: {}", created.unwrap_err());
+
+    let created = created.unwrap();
+    assert_eq!(created.retention_rules[0].every_seconds, 3600);
+
+    let update = UpdateBucketRequest::default()
+        .name("managed_bucket_renamed")
+        .retention(Duration::from_secs(7200));
+
+    let updated = client.update_bucket(&created.id, update).await;
+    assert!(updated.is_ok(), "UPDATE BUCKET DIDNT WORK: {}", updated.unwrap_err());
+    assert_eq!(updated.unwrap().name, "managed_bucket_renamed");
+
+    let fetched = client.get_bucket(&created.id).await;
+    assert!(fetched.is_ok(), "GET BUCKET DIDNT WORK: {}", fetched.unwrap_err());
+    assert_eq!(fetched.unwrap().retention_rules[0].every_seconds, 7200);
+
+    let found = client.find_buckets(&BucketFilter::default().org("test_org").name("managed_bucket_renamed")).await;
+    assert!(found.is_ok(), "FIND BUCKETS DIDNT WORK: {}", found.unwrap_err());
+    assert_eq!(found.unwrap().len(), 1);
+
+    let drop = client.drop_database("managed_bucket_renamed").await;
+    assert!(drop.is_ok());
+
+    let missing = client.get_bucket(&created.id).await.unwrap_err();
+    assert!(matches!(missing.inner, ErrorKind::NotFound(_)), "EXPECTED NOT FOUND: {}", missing);
+    assert_eq!(missing.status(), Some(404));
+    assert!(!missing.is_retryable());
+}
+
+#[tokio::test]
+async fn partial_write_names_rejected_points() {
+    // Create client with a parsed url, bucket, org, and jwt token
+    let client = Client::new(Url::parse("http://localhost:8086").unwrap(), "test_bucket", "test_org", "0123456789").await.unwrap();
+    let now = Utc::now();
+
+    let first = Point::new("partial_write").add_field("value", 1.5);
+    let write = client.write_point(first, Some(Precision::Seconds), None).await;
+    assert!(write.is_ok(), "WRITE DIDNT WORK: {}", write.unwrap_err());
+
+    // The integer conflicts with the float already stored for the field
+    let points = Points::create_new(vec![
+        Point::new("partial_write").add_field("value", 2.5),
+        Point::new("partial_write").add_field("value", 3),
+        Point::new("partial_write").add_field("other", 4.5),
+    ]);
+
+    let error = client.write_points(points, Some(Precision::Seconds), None).await.unwrap_err();
+    let write_error = error.write_error().expect("EXPECTED PARTIAL WRITE");
+    assert_eq!(write_error.rejected_indices(), vec![1]);
+    assert_eq!(write_error.rejected[0].reason, RejectReason::FieldTypeConflict);
+
+    let later = Utc::now().to_rfc3339().to_string();
+    let drop = client.drop_measurement("partial_write", &now.to_rfc3339(), &later).await;
+    assert!(drop.is_ok());
+}